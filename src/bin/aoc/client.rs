@@ -0,0 +1,173 @@
+use std::{error::Error, fs, path::Path};
+
+static DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+static YEAR: u32 = 2024;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    Incorrect,
+    TooSoon,
+}
+
+/// Talks to the Advent of Code site, or anything that looks like it at
+/// `base_url`.
+pub struct Client {
+    base_url: String,
+    session: String,
+}
+
+impl Client {
+    pub fn new(base_url: &str, session: &str) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            session: session.to_string(),
+        }
+    }
+
+    /// Uses the session cookie in `AOC_SESSION`, and `AOC_BASE_URL` if it is
+    /// set to talk to somewhere other than the real site.
+    pub fn from_env() -> Result<Self, Box<dyn Error>> {
+        let session = std::env::var("AOC_SESSION").map_err(|_| "AOC_SESSION isn't set")?;
+        let base_url =
+            std::env::var("AOC_BASE_URL").unwrap_or_else(|_| DEFAULT_BASE_URL.to_string());
+        Ok(Self::new(&base_url, &session))
+    }
+
+    fn day_url(&self, day: u32) -> String {
+        format!("{}/{}/day/{}", self.base_url, YEAR, day)
+    }
+
+    fn cookie(&self) -> String {
+        format!("session={}", self.session)
+    }
+
+    /// Makes sure the input for `day` is in `inputs/` under `root`, only
+    /// downloading it if it isn't there. The empty placeholder left by
+    /// `aoc new` doesn't count as being there.
+    pub fn fetch_input(&self, root: &Path, day: u32) -> Result<bool, Box<dyn Error>> {
+        let path = root.join("inputs").join(format!("day_{day:02}.txt"));
+        if fs::metadata(&path).is_ok_and(|x| x.len() != 0) {
+            return Ok(false);
+        }
+
+        let input = ureq::get(&format!("{}/input", self.day_url(day)))
+            .set("Cookie", &self.cookie())
+            .call()?
+            .into_string()?;
+
+        fs::create_dir_all(path.parent().unwrap())?;
+        fs::write(path, input)?;
+        Ok(true)
+    }
+
+    pub fn submit(&self, day: u32, part: u32, answer: &str) -> Result<Verdict, Box<dyn Error>> {
+        let response = ureq::post(&format!("{}/answer", self.day_url(day)))
+            .set("Cookie", &self.cookie())
+            .send_form(&[("level", &part.to_string()), ("answer", answer)])?
+            .into_string()?;
+
+        if response.contains("That's the right answer") {
+            Ok(Verdict::Correct)
+        } else if response.contains("That's not the right answer") {
+            Ok(Verdict::Incorrect)
+        } else if response.contains("You gave an answer too recently") {
+            Ok(Verdict::TooSoon)
+        } else {
+            Err("didn't understand the response to the answer".into())
+        }
+    }
+}
+
+/// Serves one canned response per expected request on a local port, and
+/// returns what was asked for once they have all been served.
+#[cfg(test)]
+fn mock_server(responses: Vec<&'static str>) -> (String, std::thread::JoinHandle<Vec<String>>) {
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+    };
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+
+    let handle = std::thread::spawn(move || {
+        let mut requests = Vec::new();
+        for response in responses {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+
+            let mut request = String::new();
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if let Some(length) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                    content_length = length.trim().parse().unwrap();
+                }
+                if line == "\r\n" {
+                    break;
+                }
+                request.push_str(&line);
+            }
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).unwrap();
+            request.push_str(&String::from_utf8(body).unwrap());
+            requests.push(request);
+
+            write!(
+                reader.get_mut(),
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                response.len(),
+                response
+            )
+            .unwrap();
+        }
+        requests
+    });
+
+    (base_url, handle)
+}
+
+#[test]
+fn check_fetch_input() {
+    let dir = aoc24::testing::TempDir::new("client");
+    let root = dir.path();
+    fs::create_dir_all(root.join("inputs")).unwrap();
+    fs::write(root.join("inputs/day_03.txt"), "").unwrap();
+
+    let (base_url, server) = mock_server(vec!["1 2 3\n"]);
+    let client = Client::new(&base_url, "abc");
+
+    assert!(client.fetch_input(root, 3).unwrap());
+    // the second time it is already on disk, so the server isn't asked again
+    assert!(!client.fetch_input(root, 3).unwrap());
+
+    assert_eq!(
+        fs::read_to_string(root.join("inputs/day_03.txt")).unwrap(),
+        "1 2 3\n"
+    );
+
+    let requests = server.join().unwrap();
+    assert_eq!(requests.len(), 1);
+    assert!(requests[0].starts_with("GET /2024/day/3/input HTTP/1.1\r\n"));
+    assert!(requests[0].contains("session=abc"));
+}
+
+#[test]
+fn check_submit() {
+    let (base_url, server) = mock_server(vec![
+        "<article><p>That's the right answer!</p></article>",
+        "<article><p>That's not the right answer.</p></article>",
+        "<article><p>You gave an answer too recently.</p></article>",
+    ]);
+    let client = Client::new(&base_url, "abc");
+
+    assert_eq!(client.submit(5, 1, "143").unwrap(), Verdict::Correct);
+    assert_eq!(client.submit(5, 2, "1").unwrap(), Verdict::Incorrect);
+    assert_eq!(client.submit(5, 2, "123").unwrap(), Verdict::TooSoon);
+
+    let requests = server.join().unwrap();
+    assert!(requests[0].starts_with("POST /2024/day/5/answer HTTP/1.1\r\n"));
+    assert!(requests[0].ends_with("level=1&answer=143"));
+}
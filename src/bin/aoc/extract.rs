@@ -0,0 +1,112 @@
+use aoc24::examples::Example;
+use regex::Regex;
+
+fn decode(html: &str) -> String {
+    Regex::new(r"<[^>]*>")
+        .unwrap()
+        .replace_all(html, "")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Pulls the examples out of a saved puzzle description. Each part's answer
+/// is taken to be the last emphasized code in its `<article>`, and belongs to
+/// the first `<pre><code>` block of that article, or to the previous part's
+/// example if the article has none. Descriptions that walk through several
+/// examples need the result checking by hand.
+pub fn extract(html: &str, day: u32) -> Vec<Example> {
+    let article = Regex::new(r"(?s)<article[^>]*>(.*?)</article>").unwrap();
+    let pre = Regex::new(r"(?s)<pre><code>(.*?)</code></pre>").unwrap();
+    let answer =
+        Regex::new(r"<code><em>([^<]*)</em></code>|<em><code>([^<]*)</code></em>").unwrap();
+
+    let mut examples: Vec<Example> = Vec::new();
+
+    for (part, article) in article.captures_iter(html).enumerate() {
+        let text = &article[1];
+        let prose = pre.replace_all(text, "");
+        let Some(last) = answer.captures_iter(&prose).last() else {
+            continue;
+        };
+        let part = format!("part_{}", part + 1);
+        let value = decode(last.get(1).or(last.get(2)).unwrap().as_str());
+
+        let example = match pre.captures(text) {
+            Some(block) => {
+                let input = decode(&block[1]);
+                match examples.iter().position(|x| x.input == input) {
+                    Some(i) => &mut examples[i],
+                    None => {
+                        examples.push(Example {
+                            name: format!("day_{:02}_{}", day, examples.len() + 1),
+                            input,
+                            answers: Vec::new(),
+                        });
+                        examples.last_mut().unwrap()
+                    }
+                }
+            }
+            None => match examples.last_mut() {
+                Some(example) => example,
+                None => continue,
+            },
+        };
+
+        example.answers.push((part, value));
+    }
+
+    examples
+}
+
+#[test]
+fn check_extract() {
+    let html = r#"<main>
+<article class="day-desc"><h2>--- Day 1: Historian Hysteria ---</h2>
+<p>For example:</p>
+<pre><code>3   4
+4   3
+</code></pre>
+<p>Then, <em>pair up</em> the numbers:</p>
+<pre><code>3 &lt;-&gt; <em>4</em>
+</code></pre>
+<p>In the example above, this is <code>1 + 1</code>, a total of <code><em>2</em></code>.</p>
+</article>
+<p>Your puzzle answer was <code>1234</code>.</p>
+<article class="day-desc"><h2 id="part2">--- Part Two ---</h2>
+<p>So, for these example lists, the similarity score is <code><em>12</em></code>.</p>
+</article>
+</main>"#;
+
+    assert_eq!(
+        extract(html, 1),
+        [Example {
+            name: "day_01_1".to_string(),
+            input: "3   4\n4   3\n".to_string(),
+            answers: vec![
+                ("part_1".to_string(), "2".to_string()),
+                ("part_2".to_string(), "12".to_string())
+            ],
+        }]
+    );
+}
+
+#[test]
+fn check_extract_new_example() {
+    let html = r#"<article class="day-desc">
+<pre><code>mul(2,4)&amp;mul[3,7]</code></pre>
+<p>... produces <code><em>161</em></code>.</p>
+</article>
+<article class="day-desc">
+<pre><code>do()don't()mul(5,5)</code></pre>
+<p>... is <code><em>48</em></code>.</p>
+</article>"#;
+
+    let examples = extract(html, 3);
+    assert_eq!(examples.len(), 2);
+    assert_eq!(examples[0].input, "mul(2,4)&mul[3,7]");
+    assert_eq!(examples[1].name, "day_03_2");
+    assert_eq!(examples[1].answer("part_2"), Some("48"));
+}
@@ -0,0 +1,102 @@
+use std::{error::Error, path::Path, process::Command};
+
+mod client;
+mod days;
+mod extract;
+mod scaffold;
+
+/// Runs a day's solver, which has to have been built already with
+/// `cargo build`, and returns what it printed.
+fn run_day(day: u32) -> Result<String, Box<dyn Error>> {
+    let name = format!("day_{day:02}");
+    let executable = std::env::current_exe()?.with_file_name(&name);
+    if !executable.exists() {
+        return Err(format!("{name} has not been built, run `cargo build` first").into());
+    }
+
+    let output = Command::new(executable).output()?;
+    if !output.status.success() {
+        // an error returned from a solver's `main` is printed after `Error: `
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let stderr = stderr.trim();
+        return Err(stderr.strip_prefix("Error: ").unwrap_or(stderr).into());
+    }
+
+    Ok(String::from_utf8(output.stdout)?)
+}
+
+fn run(days: &[u32]) {
+    let run_day = |&day: &u32| run_day(day).map_err(|e| e.to_string());
+
+    // collected before printing anything, so the output stays in day order
+    let outputs: Vec<_> = aoc24::maybe_par_iter!(days, |iter| iter.map(run_day).collect());
+
+    for (&day, output) in days.iter().zip(outputs) {
+        println!("Day {day}");
+        match output {
+            Ok(output) => print!("{output}"),
+            Err(e) => println!("day {day}: {e}"),
+        }
+    }
+}
+
+fn parse_day(day: Option<String>) -> Result<u32, Box<dyn Error>> {
+    let day = day.ok_or("expected a day")?;
+    Ok(day.parse().map_err(|_| format!("`{day}` isn't a day"))?)
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let mut args = std::env::args().skip(1);
+
+    match args.next().as_deref() {
+        None => run(days::DAYS),
+        Some("run") => {
+            let days = args
+                .map(|x| parse_day(Some(x)))
+                .collect::<Result<Vec<_>, _>>()?;
+            if days.is_empty() {
+                run(days::DAYS);
+            } else {
+                run(&days);
+            }
+        }
+        Some("new") => scaffold::new_day(
+            Path::new(env!("CARGO_MANIFEST_DIR")),
+            parse_day(args.next())?,
+        )?,
+        Some("fetch") => {
+            let day = parse_day(args.next())?;
+            let client = client::Client::from_env()?;
+            if client.fetch_input(Path::new(env!("CARGO_MANIFEST_DIR")), day)? {
+                println!("Downloaded the input for day {day}");
+            } else {
+                println!("Already have the input for day {day}");
+            }
+        }
+        Some("submit") => {
+            let day = parse_day(args.next())?;
+            let part = match args.next().as_deref() {
+                Some("1") => 1,
+                Some("2") => 2,
+                _ => return Err("expected a part, 1 or 2".into()),
+            };
+            let answer = args.next().ok_or("expected an answer")?;
+            let verdict = client::Client::from_env()?.submit(day, part, &answer)?;
+            println!("{verdict:?}");
+        }
+        Some("examples") => {
+            let day = parse_day(args.next())?;
+            let html = std::fs::read_to_string(args.next().ok_or("expected a saved puzzle page")?)?;
+            for example in extract::extract(&html, day) {
+                aoc24::examples::save(Path::new(env!("CARGO_MANIFEST_DIR")), &example)?;
+                println!("{}: {:?}", example.name, example.answers);
+            }
+        }
+        Some(command) => {
+            let expected = "`run`, `new`, `fetch`, `submit` or `examples`";
+            return Err(format!("unknown command `{command}`, expected {expected}").into());
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,178 @@
+use std::{error::Error, fs, path::Path};
+
+static TEMPLATE: &str = r#"use aoc24::parse::{self, ParseError};
+
+static TEST_INPUT_PART_1: &str = "";
+
+static TEST_INPUT_PART_2: &str = TEST_INPUT_PART_1;
+
+fn parse(input: &str) -> Result<Vec<i64>, ParseError> {
+    parse::lines(input, |line| line.parse("integer"))
+}
+
+fn part_1(input: &str) -> Result<i64, ParseError> {
+    let _numbers = parse(input)?;
+    todo!()
+}
+
+fn part_2(input: &str) -> Result<i64, ParseError> {
+    let _numbers = parse(input)?;
+    todo!()
+}
+
+static MY_INPUT: &str = aoc24::load_input!();
+
+fn main() -> Result<(), ParseError> {
+    println!("Part 1: {}", part_1(MY_INPUT)?);
+    println!("Part 2: {}", part_2(MY_INPUT)?);
+    Ok(())
+}
+
+aoc24::examples! {
+    part_1: [
+        example: TEST_INPUT_PART_1 => 0,
+    ],
+    part_2: [
+        example: TEST_INPUT_PART_2 => 0,
+    ],
+}
+"#;
+
+/// Adds `day` to the end of the list in `days.rs`, unless it is already
+/// there. A list written one day per line gets a new line, otherwise the day
+/// goes on the end of the last line.
+fn register(days_source: &str, day: u32) -> Result<String, Box<dyn Error>> {
+    let end = days_source
+        .rfind("];")
+        .ok_or("couldn't find the end of the list of days")?;
+    let start = days_source[..end]
+        .rfind("&[")
+        .ok_or("couldn't find the start of the list of days")?
+        + 2;
+    let list = &days_source[start..end];
+
+    let days = list
+        .split(',')
+        .map(str::trim)
+        .filter(|x| !x.is_empty())
+        .map(|x| x.parse::<u32>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| "couldn't read the list of days")?;
+    if days.contains(&day) {
+        return Ok(days_source.to_string());
+    }
+
+    let last_item = start + list.trim_end().trim_end_matches(',').len();
+    let last_line = days_source[..last_item].rsplit('\n').next().unwrap();
+    let one_per_line = last_line.trim().parse::<u32>().is_ok();
+
+    let (before, after) = if one_per_line {
+        (&days_source[..end], &days_source[end..])
+    } else {
+        (&days_source[..last_item], &days_source[last_item..])
+    };
+    let inserted = if one_per_line {
+        let indent = &last_line[..last_line.len() - last_line.trim_start().len()];
+        format!("{indent}{day},\n")
+    } else if days.is_empty() {
+        day.to_string()
+    } else {
+        format!(", {day}")
+    };
+
+    Ok(format!("{before}{inserted}{after}"))
+}
+
+/// Writes the solver skeleton for `day` into the crate at `root`, creates an
+/// empty input for it and registers it with the runner. Refuses to touch a
+/// solver that already exists.
+pub fn new_day(root: &Path, day: u32) -> Result<(), Box<dyn Error>> {
+    if !(1..=25).contains(&day) {
+        return Err(format!("there is no day {day}").into());
+    }
+
+    let name = format!("day_{day:02}");
+
+    let solver = root.join("src/bin").join(format!("{name}.rs"));
+    if solver.exists() {
+        return Err(format!("{} already exists", solver.display()).into());
+    }
+
+    let input = root.join("inputs").join(format!("{name}.txt"));
+    if !input.exists() {
+        fs::create_dir_all(input.parent().unwrap())?;
+        fs::write(&input, "")?;
+    }
+
+    let days = root.join("src/bin/aoc/days.rs");
+    let registered = register(&fs::read_to_string(&days)?, day)?;
+
+    fs::write(&solver, TEMPLATE)?;
+    fs::write(&days, registered)?;
+
+    println!("Created {}", solver.display());
+    Ok(())
+}
+
+#[test]
+fn check_register() {
+    let source = "pub static DAYS: &[u32] = &[\n    1,\n    2,\n];\n";
+    assert_eq!(
+        register(source, 3).unwrap(),
+        "pub static DAYS: &[u32] = &[\n    1,\n    2,\n    3,\n];\n"
+    );
+    assert_eq!(register(source, 2).unwrap(), source);
+
+    let source = "pub static DAYS: &[u32] = &[\n    1, 2, 3, 10, 11,\n];\n";
+    assert_eq!(register(source, 1).unwrap(), source);
+    assert_eq!(register(source, 11).unwrap(), source);
+    assert_eq!(
+        register(source, 12).unwrap(),
+        "pub static DAYS: &[u32] = &[\n    1, 2, 3, 10, 11, 12,\n];\n"
+    );
+
+    let source = "pub static DAYS: &[u32] = &[1, 2];\n";
+    assert_eq!(register(source, 2).unwrap(), source);
+    assert_eq!(
+        register(source, 3).unwrap(),
+        "pub static DAYS: &[u32] = &[1, 2, 3];\n"
+    );
+    assert_eq!(
+        register("pub static DAYS: &[u32] = &[];\n", 1).unwrap(),
+        "pub static DAYS: &[u32] = &[1];\n"
+    );
+    assert!(register("pub static DAYS: &[u32] = &[1, x];\n", 2).is_err());
+}
+
+#[test]
+fn check_new_day() {
+    let dir = aoc24::testing::TempDir::new("scaffold");
+    let root = dir.path();
+    fs::create_dir_all(root.join("src/bin/aoc")).unwrap();
+    fs::write(
+        root.join("src/bin/aoc/days.rs"),
+        "pub static DAYS: &[u32] = &[\n    1,\n];\n",
+    )
+    .unwrap();
+
+    new_day(root, 2).unwrap();
+    let solver = fs::read_to_string(root.join("src/bin/day_02.rs")).unwrap();
+    assert_eq!(solver, TEMPLATE);
+    assert!(solver.contains("fn main() -> Result<(), ParseError>"));
+    assert!(solver.contains("aoc24::examples! {"));
+    assert_eq!(
+        fs::read_to_string(root.join("inputs/day_02.txt")).unwrap(),
+        ""
+    );
+    assert!(fs::read_to_string(root.join("src/bin/aoc/days.rs"))
+        .unwrap()
+        .contains("    2,\n];"));
+
+    fs::write(root.join("src/bin/day_02.rs"), "solved").unwrap();
+    assert!(new_day(root, 2).is_err());
+    assert_eq!(
+        fs::read_to_string(root.join("src/bin/day_02.rs")).unwrap(),
+        "solved"
+    );
+    assert!(new_day(root, 26).is_err());
+}
@@ -1,4 +1,6 @@
-use itertools::Itertools;
+use std::fmt::Display;
+
+use aoc24::parse::{self, ParseError};
 
 static TEST_INPUT_PART_1: &str = "7 6 4 2 1
 1 2 7 8 9
@@ -8,78 +10,206 @@ static TEST_INPUT_PART_1: &str = "7 6 4 2 1
 1 3 6 7 9
 ";
 
-fn is_safe(x: impl Iterator<Item = i64> + Clone) -> bool {
-    let differences = x.tuple_windows().map(|(a, b)| a - b);
-    if differences.clone().any(|x| x.abs() > 3) {
-        return false;
-    }
+fn parse(input: &str) -> Result<Vec<Vec<i64>>, ParseError> {
+    parse::lines(input, |line| {
+        line.split_whitespace()
+            .map(|x| x.parse("integer"))
+            .collect()
+    })
+}
 
-    let sign_of_first = differences.clone().next().unwrap().signum();
+fn is_step_safe(a: i64, b: i64, direction: i64) -> bool {
+    (1..=3).contains(&((b - a) * direction))
+}
 
-    if differences.clone().any(|x| x.signum() != sign_of_first) {
-        return false;
+/// Finds the first pair of levels `(idx, idx + 1)` that can't follow each
+/// other in a report going in `direction`, ignoring the level at `skip`.
+fn first_unsafe_step(levels: &[i64], direction: i64, skip: Option<usize>) -> Option<usize> {
+    let mut previous: Option<usize> = None;
+    for idx in (0..levels.len()).filter(|&idx| Some(idx) != skip) {
+        if let Some(previous) = previous {
+            if !is_step_safe(levels[previous], levels[idx], direction) {
+                return Some(previous);
+            }
+        }
+        previous = Some(idx);
     }
 
-    true
+    None
 }
 
-fn is_safe_with_skip(input: &[i64]) -> bool {
-    if is_safe(input.iter().cloned()) {
-        return true;
-    }
-    for i in 0..input.len() {
-        if is_safe(
-            input
-                .iter()
-                .cloned()
-                .enumerate()
-                .filter(|(idx, _)| *idx != i)
-                .map(|(_, x)| x),
-        ) {
-            return true;
+fn is_safe(levels: &[i64]) -> bool {
+    [1, -1]
+        .into_iter()
+        .any(|direction| first_unsafe_step(levels, direction, None).is_none())
+}
+
+/// Finds a level that can be removed to make the report safe, or `None` if
+/// there isn't one. Only the two levels either side of the first bad step
+/// can help, so this is a constant number of linear scans.
+fn removal_fixing(levels: &[i64]) -> Option<usize> {
+    [1, -1].into_iter().find_map(|direction| {
+        let idx = first_unsafe_step(levels, direction, None)?;
+        [idx, idx + 1]
+            .into_iter()
+            .find(|&skip| first_unsafe_step(levels, direction, Some(skip)).is_none())
+    })
+}
+
+fn is_safe_with_skip(levels: &[i64]) -> bool {
+    is_safe(levels) || removal_fixing(levels).is_some()
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Problem {
+    /// The level at `index` goes the other way to the levels before it.
+    DirectionFlip { index: usize },
+    /// The level at `index` differs from the one before it by `size`, which
+    /// isn't between 1 and 3.
+    Jump { index: usize, size: i64 },
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Explanation {
+    problem: Problem,
+    fix: Option<usize>,
+}
+
+impl Display for Explanation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.problem {
+            Problem::DirectionFlip { index } => write!(f, "direction flips at index {}", index)?,
+            Problem::Jump { index, size } => write!(f, "jump of {} at index {}", size, index)?,
+        }
+        match self.fix {
+            Some(fix) => write!(f, ", fixed by removing index {}", fix),
+            None => write!(f, ", no single removal fixes it"),
         }
     }
-
-    false
 }
 
-fn part_1(input: &str) -> i64 {
-    input
-        .lines()
-        .map(|x| {
-            x.split_whitespace()
-                .map(|x| x.parse::<i64>().unwrap())
-                .collect::<Vec<_>>()
+/// Explains why a report is unsafe, or returns `None` if it is safe.
+fn explain(levels: &[i64]) -> Option<Explanation> {
+    if is_safe(levels) {
+        return None;
+    }
+
+    let mut direction = 0;
+    let problem = levels
+        .windows(2)
+        .enumerate()
+        .find_map(|(index, pair)| {
+            let index = index + 1;
+            let size = pair[1] - pair[0];
+            if !(1..=3).contains(&size.abs()) {
+                return Some(Problem::Jump { index, size });
+            }
+            if direction == 0 {
+                direction = size.signum();
+            }
+            (size.signum() != direction).then_some(Problem::DirectionFlip { index })
         })
-        .filter(|x| is_safe(x.iter().cloned()))
-        .count() as i64
+        .expect("an unsafe report should have a bad step");
+
+    Some(Explanation {
+        problem,
+        fix: removal_fixing(levels),
+    })
 }
 
-fn part_2(input: &str) -> i64 {
-    input
-        .lines()
-        .map(|x| {
-            x.split_whitespace()
-                .map(|x| x.parse::<i64>().unwrap())
-                .collect::<Vec<_>>()
-        })
-        .filter(|x| is_safe_with_skip(x))
-        .count() as i64
+fn part_1(input: &str) -> Result<i64, ParseError> {
+    let reports = parse(input)?;
+    Ok(aoc24::maybe_par_iter!(reports, |iter| iter.filter(|x| is_safe(x)).count()) as i64)
+}
+
+fn part_2(input: &str) -> Result<i64, ParseError> {
+    let reports = parse(input)?;
+    Ok(aoc24::maybe_par_iter!(reports, |iter| {
+        iter.filter(|x| is_safe_with_skip(x)).count()
+    }) as i64)
 }
 
 static MY_INPUT: &str = aoc24::load_input!();
 
-fn main() {
-    println!("Part 1: {}", part_1(MY_INPUT));
-    println!("Part 2: {}", part_2(MY_INPUT));
+fn main() -> Result<(), ParseError> {
+    if std::env::args().any(|x| x == "--explain") {
+        for (line, levels) in parse(MY_INPUT)?.iter().enumerate() {
+            if let Some(explanation) = explain(levels) {
+                println!("{}: {}", line + 1, explanation);
+            }
+        }
+    }
+
+    println!("Part 1: {}", part_1(MY_INPUT)?);
+    println!("Part 2: {}", part_2(MY_INPUT)?);
+    Ok(())
+}
+
+aoc24::examples! {
+    part_1: [
+        example: TEST_INPUT_PART_1 => 2,
+    ],
+    part_2: [
+        example: TEST_INPUT_PART_1 => 4,
+    ],
 }
 
 #[test]
-fn test_part_1() {
-    assert_eq!(part_1(TEST_INPUT_PART_1), 2);
+fn test_short_reports() {
+    assert!(is_safe(&[]));
+    assert!(is_safe(&[5]));
+    assert!(is_safe_with_skip(&[5, 50]));
+    assert_eq!(part_2("\n7\n1 9\n"), Ok(3));
+}
+
+// deterministic random numbers for the brute force comparison
+#[cfg(test)]
+struct Lcg(u64);
+
+#[cfg(test)]
+impl Lcg {
+    fn below(&mut self, n: u64) -> u64 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (self.0 >> 33) % n
+    }
 }
 
 #[test]
-fn test_part_2() {
-    assert_eq!(part_2(TEST_INPUT_PART_1), 4);
+fn test_removal_matches_brute_force() {
+    let mut lcg = Lcg(2);
+    for _ in 0..2000 {
+        let len = lcg.below(8);
+        let levels: Vec<i64> = (0..len).map(|_| lcg.below(10) as i64).collect();
+
+        let brute_force = is_safe(&levels)
+            || (0..levels.len()).any(|skip| {
+                let mut levels = levels.clone();
+                levels.remove(skip);
+                is_safe(&levels)
+            });
+        assert_eq!(is_safe_with_skip(&levels), brute_force, "{levels:?}");
+    }
+}
+
+#[test]
+fn test_explain() {
+    assert_eq!(explain(&[7, 6, 4, 2, 1]), None);
+    assert_eq!(
+        explain(&[1, 2, 7, 8, 9]),
+        Some(Explanation {
+            problem: Problem::Jump { index: 2, size: 5 },
+            fix: None
+        })
+    );
+    assert_eq!(
+        explain(&[1, 3, 2, 4, 5]).unwrap().to_string(),
+        "direction flips at index 2, fixed by removing index 1"
+    );
+    assert_eq!(
+        explain(&[8, 6, 4, 4, 1]).unwrap().to_string(),
+        "jump of 0 at index 3, fixed by removing index 2"
+    );
 }
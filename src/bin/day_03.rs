@@ -3,47 +3,195 @@ static TEST_INPUT_PART_1: &str =
 static TEST_INPUT_PART_2: &str =
     "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
 
-fn parse_mul(input: &str) -> Option<i64> {
-    let input = input.strip_prefix("mul(")?;
-    let (first_number, rest) = input.split_once(',')?;
-    let (second_number, _rest) = rest.split_once(')')?;
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Instruction {
+    Mul(i64, i64),
+    Do,
+    Dont,
+}
 
-    Some(first_number.parse::<i64>().ok()? * second_number.parse::<i64>().ok()?)
+/// How an instruction is written: `name(a,b,...)` with exactly `arity`
+/// operands of 1-3 digits each.
+struct Syntax {
+    name: &'static str,
+    arity: usize,
+    build: fn(&[i64]) -> Instruction,
 }
 
-fn part_1(input: &str) -> i64 {
-    input
-        .char_indices()
-        .map(|(idx, _)| &input[idx..])
-        .flat_map(parse_mul)
-        .sum()
+static INSTRUCTION_SET: &[Syntax] = &[
+    Syntax {
+        name: "mul",
+        arity: 2,
+        build: |x| Instruction::Mul(x[0], x[1]),
+    },
+    Syntax {
+        name: "do",
+        arity: 0,
+        build: |_| Instruction::Do,
+    },
+    Syntax {
+        name: "don't",
+        arity: 0,
+        build: |_| Instruction::Dont,
+    },
+];
+
+const MAX_ARITY: usize = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Token {
+    instruction: Instruction,
+    start: usize,
+    end: usize,
 }
 
-fn part_2(input: &str) -> i64 {
-    let mut enabled = true;
-    let mut sum = 0;
-    for substr in input.char_indices().map(|(idx, _)| &input[idx..]) {
-        if substr.starts_with("do()") {
-            enabled = true;
+struct Lexer<'a> {
+    input: &'a [u8],
+    idx: usize,
+}
+
+impl<'a> Lexer<'a> {
+    fn new(input: &'a str) -> Self {
+        Self {
+            input: input.as_bytes(),
+            idx: 0,
+        }
+    }
+
+    fn operand(&self, idx: &mut usize) -> Option<i64> {
+        let digits = self.input[*idx..]
+            .iter()
+            .take_while(|x| x.is_ascii_digit())
+            .count();
+        if !(1..=3).contains(&digits) {
+            return None;
         }
 
-        if substr.starts_with("don't()") {
-            enabled = false;
+        let value = self.input[*idx..*idx + digits]
+            .iter()
+            .fold(0, |acc, x| acc * 10 + (x - b'0') as i64);
+        *idx += digits;
+        Some(value)
+    }
+
+    fn expect(&self, idx: &mut usize, literal: &[u8]) -> Option<()> {
+        if self.input[*idx..].starts_with(literal) {
+            *idx += literal.len();
+            Some(())
+        } else {
+            None
         }
+    }
+
+    fn instruction(&self, syntax: &Syntax) -> Option<Token> {
+        let mut idx = self.idx;
+        self.expect(&mut idx, syntax.name.as_bytes())?;
+        self.expect(&mut idx, b"(")?;
 
-        if enabled {
-            if let Some(num) = parse_mul(substr) {
-                sum += num;
+        let mut operands = [0; MAX_ARITY];
+        for (n, operand) in operands[..syntax.arity].iter_mut().enumerate() {
+            if n != 0 {
+                self.expect(&mut idx, b",")?;
             }
+            *operand = self.operand(&mut idx)?;
+        }
+
+        self.expect(&mut idx, b")")?;
+
+        Some(Token {
+            instruction: (syntax.build)(&operands[..syntax.arity]),
+            start: self.idx,
+            end: idx,
+        })
+    }
+}
+
+impl Iterator for Lexer<'_> {
+    type Item = Token;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.idx < self.input.len() {
+            if let Some(token) = INSTRUCTION_SET
+                .iter()
+                .find_map(|syntax| self.instruction(syntax))
+            {
+                self.idx = token.end;
+                return Some(token);
+            }
+            self.idx += 1;
+        }
+
+        None
+    }
+}
+
+/// Runs the program, returning the `mul` tokens that count towards the total.
+/// With `conditionals` off, `do()` and `don't()` are ignored.
+fn counted_muls(input: &str, conditionals: bool) -> impl Iterator<Item = Token> + use<'_> {
+    let mut enabled = true;
+    Lexer::new(input).filter(move |token| match token.instruction {
+        Instruction::Mul(..) => enabled,
+        Instruction::Do => {
+            enabled = true;
+            false
+        }
+        Instruction::Dont => {
+            enabled = !conditionals;
+            false
         }
+    })
+}
+
+fn sum_muls(input: &str, conditionals: bool) -> i64 {
+    counted_muls(input, conditionals)
+        .map(|token| match token.instruction {
+            Instruction::Mul(a, b) => a * b,
+            _ => unreachable!(),
+        })
+        .sum()
+}
+
+/// Colours the input for a terminal: `mul`s that count are green, `mul`s that
+/// are switched off are red and `do()`/`don't()` are blue.
+fn highlight(input: &str, conditionals: bool) -> String {
+    let counted: Vec<_> = counted_muls(input, conditionals).collect();
+
+    let mut output = String::new();
+    let mut last = 0;
+    for token in Lexer::new(input) {
+        let colour = match token.instruction {
+            Instruction::Mul(..) if counted.contains(&token) => "32",
+            Instruction::Mul(..) => "31",
+            Instruction::Do | Instruction::Dont if conditionals => "34",
+            Instruction::Do | Instruction::Dont => continue,
+        };
+        output.push_str(&input[last..token.start]);
+        output.push_str(&format!(
+            "\x1b[{colour}m{}\x1b[0m",
+            &input[token.start..token.end]
+        ));
+        last = token.end;
     }
+    output.push_str(&input[last..]);
 
-    sum
+    output
+}
+
+fn part_1(input: &str) -> i64 {
+    sum_muls(input, false)
+}
+
+fn part_2(input: &str) -> i64 {
+    sum_muls(input, true)
 }
 
 static MY_INPUT: &str = aoc24::load_input!();
 
 fn main() {
+    if std::env::args().any(|x| x == "--highlight") {
+        println!("{}", highlight(MY_INPUT, true));
+    }
+
     println!("Part 1: {}", part_1(MY_INPUT));
     println!("Part 2: {}", part_2(MY_INPUT));
 }
@@ -54,11 +202,64 @@ fn check_parse() {
 }
 
 #[test]
-fn check_part_1() {
-    assert_eq!(part_1(TEST_INPUT_PART_1), 161);
+fn check_lexer() {
+    let tokens: Vec<_> = Lexer::new(TEST_INPUT_PART_2).collect();
+    assert_eq!(
+        tokens,
+        [
+            Token {
+                instruction: Instruction::Mul(2, 4),
+                start: 1,
+                end: 9
+            },
+            Token {
+                instruction: Instruction::Dont,
+                start: 20,
+                end: 27
+            },
+            Token {
+                instruction: Instruction::Mul(5, 5),
+                start: 28,
+                end: 36
+            },
+            Token {
+                instruction: Instruction::Mul(11, 8),
+                start: 48,
+                end: 57
+            },
+            Token {
+                instruction: Instruction::Do,
+                start: 59,
+                end: 63
+            },
+            Token {
+                instruction: Instruction::Mul(8, 5),
+                start: 64,
+                end: 72
+            },
+        ]
+    );
+}
+
+#[test]
+fn check_operand_length() {
+    assert_eq!(part_1("mul(123,4)mul(1234,5)mul(1,)mul( 1,2)mul(1,2"), 492);
+    assert_eq!(part_1("mul(0001,2)mul(999,999)"), 998001);
 }
 
 #[test]
-fn check_part_2() {
-    assert_eq!(part_2(TEST_INPUT_PART_2), 48);
+fn check_highlight() {
+    assert_eq!(
+        highlight("mul(1,2)don't()mul(3,4)xdo()", true),
+        "\x1b[32mmul(1,2)\x1b[0m\x1b[34mdon't()\x1b[0m\x1b[31mmul(3,4)\x1b[0mx\x1b[34mdo()\x1b[0m"
+    );
+}
+
+aoc24::examples! {
+    part_1: [
+        example: TEST_INPUT_PART_1 => 161,
+    ],
+    part_2: [
+        example: TEST_INPUT_PART_2 => 48,
+    ],
 }
@@ -1,4 +1,13 @@
-use std::{cmp::Ordering, collections::HashMap};
+use std::fmt;
+
+use aoc24::{
+    parse::{ParseError, Span},
+    Report,
+};
+use petgraph::{
+    algo::{tarjan_scc, toposort},
+    graphmap::DiGraphMap,
+};
 
 static TEST_INPUT_PART_1: &str = "47|53
 97|13
@@ -32,31 +41,51 @@ static TEST_INPUT_PART_1: &str = "47|53
 
 static TEST_INPUT_PART_2: &str = TEST_INPUT_PART_1;
 
-fn parse_rules_and_runs(input: &str) -> Option<(HashMap<i64, Vec<i64>>, Vec<Vec<i64>>)> {
-    let (rules, runs) = input.split_once("\n\n")?;
+/// The page ordering rules, with an edge from `a` to `b` for every `a|b`.
+type Rules = DiGraphMap<i64, ()>;
 
-    let mut rules_map: HashMap<i64, Vec<i64>> = HashMap::new();
+#[derive(Debug, PartialEq, Eq)]
+enum OrderError {
+    /// The rules for the pages in the run contain a cycle through these pages.
+    Cycle(Vec<i64>),
+    /// No rule says which of these two pages comes first, so the run has more
+    /// than one valid order.
+    Ambiguous(i64, i64),
+}
 
-    for (key, value) in rules.lines().map(|x| {
-        let (x, y) = x.split_once('|').unwrap();
-        (x.parse::<i64>().unwrap(), y.parse::<i64>().unwrap())
-    }) {
-        rules_map.entry(key).or_default().push(value);
+impl fmt::Display for OrderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Cycle(pages) => write!(f, "the rules for pages {pages:?} form a cycle"),
+            Self::Ambiguous(a, b) => write!(f, "no rule orders pages {a} and {b}"),
+        }
     }
+}
+
+impl std::error::Error for OrderError {}
+
+fn parse_rules_and_runs(input: &str) -> Result<(Rules, Vec<Vec<i64>>), ParseError> {
+    let (rules, runs) = Span::new(input).split_once("\n\n", "blank line")?;
+
+    let rules = rules
+        .lines()
+        .map(|x| {
+            let (x, y) = x.split_once("|", "a|b")?;
+            Ok((x.parse("integer")?, y.parse("integer")?))
+        })
+        .collect::<Result<Vec<(i64, i64)>, _>>()?;
+
     let runs = runs
         .lines()
-        .map(|x| x.split(',').map(|x| x.parse::<i64>().unwrap()).collect())
-        .collect();
-    Some((rules_map, runs))
+        .map(|x| x.split(",").map(|x| x.parse("integer")).collect())
+        .collect::<Result<_, _>>()?;
+    Ok((Rules::from_edges(rules), runs))
 }
 
-fn is_run_valid(run: &[i64], rules: &HashMap<i64, Vec<i64>>) -> bool {
-    for (idx, num) in run.iter().copied().enumerate() {
-        let Some(rule) = rules.get(&num) else {
-            continue;
-        };
-        for first in run.iter().copied().take(idx) {
-            if rule.contains(&first) {
+fn is_run_valid(run: &[i64], rules: &Rules) -> bool {
+    for (idx, &num) in run.iter().enumerate() {
+        for &first in &run[..idx] {
+            if rules.contains_edge(num, first) {
                 return false;
             }
         }
@@ -65,58 +94,112 @@ fn is_run_valid(run: &[i64], rules: &HashMap<i64, Vec<i64>>) -> bool {
     true
 }
 
-fn part_1(input: &str) -> i64 {
-    let (rules, runs) = parse_rules_and_runs(input).unwrap();
+fn part_1(input: &str) -> Result<i64, ParseError> {
+    let (rules, runs) = parse_rules_and_runs(input)?;
 
-    runs.iter()
+    Ok(runs
+        .iter()
         .filter(|run| is_run_valid(run, &rules))
         .map(|x| x[x.len() / 2])
-        .sum()
+        .sum())
 }
 
-fn order_run(run: &mut [i64], rules: &HashMap<i64, Vec<i64>>) {
-    run.sort_by(|a, b| {
-        let a_before_b = rules.get(a).map(|x| x.contains(b)).unwrap_or_default();
-        let b_before_a = rules.get(b).map(|x| x.contains(a)).unwrap_or_default();
-        if a_before_b {
-            return Ordering::Less;
-        }
-        if b_before_a {
-            return Ordering::Greater;
+/// Orders the pages of a run by topologically sorting the rules that mention
+/// only pages in the run. The order has to be unique, so every neighbouring
+/// pair in the result must have a rule between them.
+fn order_run(run: &[i64], rules: &Rules) -> Result<Vec<i64>, OrderError> {
+    let mut graph = Rules::new();
+    for &page in run {
+        graph.add_node(page);
+    }
+    for &a in run {
+        for &b in run {
+            if rules.contains_edge(a, b) {
+                graph.add_edge(a, b, ());
+            }
         }
+    }
 
-        Ordering::Equal
-    });
-}
+    let order = toposort(&graph, None).map_err(|_| {
+        let mut cycle = tarjan_scc(&graph)
+            .into_iter()
+            .find(|component| component.len() > 1)
+            .unwrap_or_default();
+        cycle.sort();
+        OrderError::Cycle(cycle)
+    })?;
+
+    if let Some(&[a, b]) = order
+        .windows(2)
+        .find(|pair| !graph.contains_edge(pair[0], pair[1]))
+    {
+        return Err(OrderError::Ambiguous(a, b));
+    }
 
-fn part_2(input: &str) -> i64 {
-    let (rules, runs) = parse_rules_and_runs(input).unwrap();
+    Ok(order)
+}
 
-    let invalid_runs = runs.iter().filter(|run| !is_run_valid(run, &rules));
+fn part_2(input: &str) -> Result<i64, Report> {
+    let (rules, runs) = parse_rules_and_runs(input)?;
 
-    invalid_runs
-        .map(|x| {
-            let mut run = x.clone();
-            order_run(&mut run, &rules);
-            run
-        })
-        .map(|x| x[x.len() / 2])
-        .sum()
+    let mut total = 0;
+    for run in runs.iter().filter(|run| !is_run_valid(run, &rules)) {
+        let order = order_run(run, &rules)?;
+        total += order[order.len() / 2];
+    }
+    Ok(total)
 }
 
 static MY_INPUT: &str = aoc24::load_input!();
 
-fn main() {
-    println!("Part 1: {}", part_1(MY_INPUT));
-    println!("Part 2: {}", part_2(MY_INPUT));
+fn main() -> Result<(), Report> {
+    println!("Part 1: {}", part_1(MY_INPUT)?);
+    println!("Part 2: {}", part_2(MY_INPUT)?);
+    Ok(())
+}
+
+aoc24::examples! {
+    part_1: [
+        example: TEST_INPUT_PART_1 => 143,
+    ],
+    part_2: [
+        example: TEST_INPUT_PART_2 => 123,
+    ],
 }
 
 #[test]
-fn check_part_1() {
-    assert_eq!(part_1(TEST_INPUT_PART_1), 143);
+fn check_order_errors() {
+    let (rules, _) = parse_rules_and_runs(TEST_INPUT_PART_1).unwrap();
+    assert_eq!(order_run(&[97, 75, 47], &rules), Ok(vec![97, 75, 47]));
+    assert_eq!(order_run(&[75, 13, 97], &rules), Ok(vec![97, 75, 13]));
+
+    let (rules, _) = parse_rules_and_runs("1|2\n2|3\n3|1\n3|4\n\n1,2\n").unwrap();
+    assert_eq!(
+        order_run(&[4, 3, 2, 1], &rules),
+        Err(OrderError::Cycle(vec![1, 2, 3]))
+    );
+    assert_eq!(order_run(&[2, 1], &rules), Ok(vec![1, 2]));
+    assert!(matches!(
+        order_run(&[1, 4], &rules),
+        Err(OrderError::Ambiguous(1, 4) | OrderError::Ambiguous(4, 1))
+    ));
+
+    let error = part_2("1|2\n2|3\n3|1\n\n3,2,1\n").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "the rules for pages [1, 2, 3] form a cycle"
+    );
 }
 
 #[test]
-fn check_part_2() {
-    assert_eq!(part_2(TEST_INPUT_PART_2), 123);
+fn check_parse_errors() {
+    let error = part_1("47|53\n97-13\n\n75,47\n").unwrap_err();
+    assert_eq!(error.to_string(), "line 2: expected `a|b`");
+
+    let error = part_1("47|53\n\n75,47\n47,x\n").unwrap_err();
+    assert_eq!((error.line, error.column), (4, 4));
+    assert_eq!(
+        part_1("47|53\n").unwrap_err().to_string(),
+        "line 1: expected `blank line`"
+    );
 }
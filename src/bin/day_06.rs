@@ -1,6 +1,8 @@
-use std::{
-    collections::HashSet,
-    ops::{Add, AddAssign, Neg},
+use std::collections::HashSet;
+
+use aoc24::{
+    parse::{ParseError, Span},
+    Grid, Vector2d,
 };
 
 static TEST_INPUT_PART_1: &str = "....#.....
@@ -17,146 +19,279 @@ static TEST_INPUT_PART_1: &str = "....#.....
 
 static TEST_INPUT_PART_2: &str = TEST_INPUT_PART_1;
 
-struct Grid {
-    width: usize,
-    height: usize,
-    text: Box<[u8]>,
+fn direction_index(direction: Vector2d) -> usize {
+    Vector2d::DIRECTIONS
+        .iter()
+        .position(|&x| x == direction)
+        .unwrap()
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
-struct Vector2d(i32, i32);
-
-impl From<(i32, i32)> for Vector2d {
-    fn from(value: (i32, i32)) -> Self {
-        Vector2d(value.0, value.1)
-    }
+/// Remembers which (position, direction) states a walk has been through.
+/// Clearing it just bumps the generation, so it can be reused for every
+/// simulation without reallocating.
+struct Visited {
+    stamps: Vec<u32>,
+    generation: u32,
 }
 
-impl Add for Vector2d {
-    type Output = Self;
+impl Visited {
+    fn new(len: usize) -> Self {
+        Self {
+            stamps: vec![0; len * Vector2d::DIRECTIONS.len()],
+            generation: 1,
+        }
+    }
 
-    fn add(self, rhs: Self) -> Self::Output {
-        Vector2d(self.0 + rhs.0, self.1 + rhs.1)
+    fn clear(&mut self) {
+        self.generation += 1;
     }
-}
 
-impl AddAssign for Vector2d {
-    fn add_assign(&mut self, rhs: Self) {
-        *self = *self + rhs;
+    /// Marks the state as visited, returning whether it already was.
+    fn insert(&mut self, index: usize, direction: Vector2d) -> bool {
+        let stamp =
+            &mut self.stamps[index * Vector2d::DIRECTIONS.len() + direction_index(direction)];
+        let seen = *stamp == self.generation;
+        *stamp = self.generation;
+        seen
     }
 }
 
-impl Neg for Vector2d {
-    type Output = Self;
-    fn neg(self) -> Self::Output {
-        Self(-self.0, -self.1)
-    }
+struct Lab {
+    grid: Grid,
+    start: Vector2d,
+    /// For each direction and cell, where the guard stops walking in that
+    /// direction because the next cell is an obstacle, or `None` if the guard
+    /// walks off the map.
+    jumps: [Vec<Option<Vector2d>>; 4],
 }
 
-impl Grid {
-    fn new(input: &str) -> Self {
-        let width = input.lines().next().unwrap().len();
-        let height = input.lines().count();
-        Self {
-            width,
-            height,
-            text: input.as_bytes().into(),
+impl Lab {
+    fn new(input: &str) -> Result<Self, ParseError> {
+        let span = Span::new(input);
+        let grid = Grid::parse(span)?;
+        let start = grid.find(b'^').ok_or_else(|| span.error("^"))?;
+
+        let mut jumps: [Vec<Option<Vector2d>>; 4] =
+            std::array::from_fn(|_| vec![None; grid.width * grid.height]);
+
+        for (direction, jumps) in Vector2d::DIRECTIONS.into_iter().zip(jumps.iter_mut()) {
+            // walk backwards against the direction from every cell on the far
+            // edge, so each cell's answer is known from the one ahead of it
+            for (mut position, _) in grid
+                .iter()
+                .filter(|&(x, _)| grid.get(x + direction).is_none())
+            {
+                let mut stop = None;
+                while let Some(cell) = grid.get(position) {
+                    if cell == b'#' {
+                        stop = Some(position - direction);
+                    } else {
+                        jumps[position.0 as usize + position.1 as usize * grid.width] = stop;
+                    }
+                    position = position - direction;
+                }
+            }
         }
+
+        Ok(Self { grid, start, jumps })
     }
 
-    fn get(&self, v: Vector2d) -> Option<u8> {
-        if !(0..self.width as i32).contains(&v.0) {
-            return None;
-        }
-        if !(0..self.height as i32).contains(&v.1) {
-            return None;
-        }
+    fn index(&self, position: Vector2d) -> usize {
+        position.0 as usize + position.1 as usize * self.grid.width
+    }
 
-        Some(self.text[(v.0 + v.1 * (self.width as i32 + 1)) as usize])
+    fn is_blocked(&self, position: Vector2d, obstacle: Option<Vector2d>) -> bool {
+        Some(position) == obstacle || self.grid.get(position) == Some(b'#')
     }
-}
 
-fn grid_iterator(width: i32, height: i32) -> impl Iterator<Item = Vector2d> {
-    (0..height).flat_map(move |y| (0..width).map(move |x| Vector2d(x, y)))
-}
+    /// Where the guard ends up walking from `position` in `direction` before
+    /// hitting something, taking the extra obstacle into account.
+    fn jump(
+        &self,
+        position: Vector2d,
+        direction: Vector2d,
+        obstacle: Option<Vector2d>,
+    ) -> Option<Vector2d> {
+        let stop = self.jumps[direction_index(direction)][self.index(position)];
+
+        if let Some(obstacle) = obstacle {
+            let offset = obstacle - position;
+            let distance = offset.manhattan();
+            let ahead = Vector2d(direction.0 * distance, direction.1 * distance) == offset;
+            let before_stop = stop.is_none_or(|stop| distance <= (stop - position).manhattan());
+            if distance > 0 && ahead && before_stop {
+                return Some(obstacle - direction);
+            }
+        }
 
-impl Vector2d {
-    fn rotate_right(self) -> Self {
-        Vector2d(-self.1, self.0)
+        stop
     }
-}
 
-fn part_1(input: &str) -> i64 {
-    let mut visited = HashSet::new();
-    let grid = Grid::new(input);
-
-    let start = grid_iterator(grid.width as i32, grid.height as i32)
-        .find(|x| grid.get(*x) == Some(b'^'))
-        .unwrap();
+    /// The guard's walk without any extra obstacles, one entry per step.
+    fn path(&self) -> Vec<(Vector2d, Vector2d)> {
+        let mut path = Vec::new();
+        let mut direction = Vector2d(0, -1);
+        let mut position = self.start;
+
+        while self.grid.get(position).is_some() {
+            while self.is_blocked(position + direction, None) {
+                direction = direction.rotate();
+            }
+            path.push((position, direction));
+            position += direction;
+        }
 
-    let mut direction = Vector2d(0, -1);
-    let mut current_position = start;
+        path
+    }
 
-    while grid.get(current_position).is_some() {
-        while grid.get(current_position + direction) == Some(b'#') {
-            direction = direction.rotate_right();
+    /// Walks from the given state with an extra obstacle, calling `on_turn`
+    /// at the start and every time the guard turns. Returns whether the guard
+    /// ends up walking in a loop.
+    fn walk_loops(
+        &self,
+        mut position: Vector2d,
+        mut direction: Vector2d,
+        obstacle: Vector2d,
+        visited: &mut Visited,
+        mut on_turn: impl FnMut(Vector2d, Vector2d),
+    ) -> bool {
+        visited.clear();
+
+        loop {
+            if visited.insert(self.index(position), direction) {
+                return true;
+            }
+            on_turn(position, direction);
+
+            let Some(stop) = self.jump(position, direction, Some(obstacle)) else {
+                return false;
+            };
+            position = stop;
+            direction = direction.rotate();
         }
-        visited.insert(current_position);
+    }
 
-        current_position += direction;
+    /// Calls `f` with every obstacle position that would trap the guard, and
+    /// the state the guard is in just before running into it for the first
+    /// time.
+    fn for_each_trap(&self, mut f: impl FnMut(Vector2d, Vector2d, Vector2d)) {
+        let mut tried = HashSet::new();
+        tried.insert(self.start);
+
+        for (position, direction) in self.path() {
+            let obstacle = position + direction;
+            if self.grid.get(obstacle).is_none() || !tried.insert(obstacle) {
+                continue;
+            }
+            f(obstacle, position, direction);
+        }
     }
+}
 
-    visited.len() as i64
+fn part_1(input: &str) -> Result<i64, ParseError> {
+    let lab = Lab::new(input)?;
+    Ok(lab
+        .path()
+        .into_iter()
+        .map(|(position, _)| position)
+        .collect::<HashSet<_>>()
+        .len() as i64)
 }
 
-fn gets_stuck(grid: &Grid, start: Vector2d, extra_position: Vector2d) -> bool {
-    let mut visited = HashSet::new();
+fn part_2(input: &str) -> Result<i64, ParseError> {
+    let lab = Lab::new(input)?;
+    let mut traps = Vec::new();
+    lab.for_each_trap(|obstacle, position, direction| traps.push((obstacle, position, direction)));
 
-    let mut direction = Vector2d(0, -1);
-    let mut current_position = start;
+    let new_visited = || Visited::new(lab.grid.width * lab.grid.height);
+    let loops = |visited: &mut Visited, &(obstacle, position, direction): &(_, _, _)| {
+        lab.walk_loops(position, direction, obstacle, visited, |_, _| {})
+    };
 
-    while grid.get(current_position).is_some() {
-        while current_position + direction == extra_position
-            || grid.get(current_position + direction) == Some(b'#')
-        {
-            direction = direction.rotate_right();
-        }
-        if visited.contains(&(current_position, direction)) {
-            return true;
-        }
-        visited.insert((current_position, direction));
-
-        current_position += direction;
-    }
+    // each thread needs its own visited stamps
+    let count = aoc24::maybe_par_iter!(traps, |iter| {
+        iter.map_init(new_visited, loops).filter(|&x| x).count()
+    });
 
-    false
+    Ok(count as i64)
 }
 
-fn part_2(input: &str) -> i64 {
-    let grid = Grid::new(input);
-
-    let start = grid_iterator(grid.width as i32, grid.height as i32)
-        .find(|x| grid.get(*x) == Some(b'^'))
-        .unwrap();
+struct LoopingPath {
+    obstacle: Vector2d,
+    /// The guard's position and direction at every turn, starting from just
+    /// before it first walks into the obstacle.
+    turns: Vec<(Vector2d, Vector2d)>,
+}
 
-    grid_iterator(grid.width as i32, grid.height as i32)
-        .filter(|x| gets_stuck(&grid, start, *x))
-        .count() as i64
+/// Every obstacle that would make the guard loop, along with the loop it
+/// causes, for drawing.
+fn looping_paths(input: &str) -> Result<Vec<LoopingPath>, ParseError> {
+    let lab = Lab::new(input)?;
+    let mut visited = Visited::new(lab.grid.width * lab.grid.height);
+
+    let mut paths = Vec::new();
+    lab.for_each_trap(|obstacle, position, direction| {
+        let mut turns = Vec::new();
+        if lab.walk_loops(position, direction, obstacle, &mut visited, |p, d| {
+            turns.push((p, d))
+        }) {
+            paths.push(LoopingPath { obstacle, turns });
+        }
+    });
+    Ok(paths)
 }
 
 static MY_INPUT: &str = aoc24::load_input!();
 
-fn main() {
-    println!("Part 1: {}", part_1(MY_INPUT));
-    println!("Part 2: {}", part_2(MY_INPUT));
+fn main() -> Result<(), ParseError> {
+    if std::env::args().any(|x| x == "--loops") {
+        for path in looping_paths(MY_INPUT)? {
+            println!("{:?}: {:?}", path.obstacle, path.turns);
+        }
+    }
+
+    println!("Part 1: {}", part_1(MY_INPUT)?);
+    println!("Part 2: {}", part_2(MY_INPUT)?);
+    Ok(())
+}
+
+aoc24::examples! {
+    part_1: [
+        example: TEST_INPUT_PART_1 => 41,
+    ],
+    part_2: [
+        example: TEST_INPUT_PART_2 => 6,
+    ],
 }
 
 #[test]
-fn check_part_1() {
-    assert_eq!(part_1(TEST_INPUT_PART_1), 41);
+fn check_looping_paths() {
+    let paths = looping_paths(TEST_INPUT_PART_2).unwrap();
+    let mut obstacles: Vec<_> = paths.iter().map(|x| (x.obstacle.1, x.obstacle.0)).collect();
+    obstacles.sort();
+    assert_eq!(obstacles, [(6, 3), (7, 6), (7, 7), (8, 1), (8, 3), (9, 7)]);
+
+    let lab = Lab::new(TEST_INPUT_PART_2).unwrap();
+    for path in paths {
+        // every stretch between turns is clear apart from the new obstacle
+        for (&(from, direction), &(to, _)) in path.turns.iter().zip(&path.turns[1..]) {
+            let mut position = from;
+            while position != to {
+                position += direction;
+                assert!(!lab.is_blocked(position, Some(path.obstacle)));
+            }
+        }
+    }
 }
 
 #[test]
-fn check_part_2() {
-    assert_eq!(part_2(TEST_INPUT_PART_2), 6);
+fn check_parse_errors() {
+    assert_eq!(
+        part_1("..\n..\n").unwrap_err().to_string(),
+        "line 1: expected `^`"
+    );
+    assert_eq!(
+        part_2("..\n.^.\n").unwrap_err().to_string(),
+        "line 2: expected `a row 2 wide`"
+    );
 }
@@ -1,3 +1,5 @@
+use aoc24::parse::{self, ParseError};
+
 static TEST_INPUT_PART_1: &str = "190: 10 19
 3267: 81 40 27
 83: 17 5
@@ -11,90 +13,310 @@ static TEST_INPUT_PART_1: &str = "190: 10 19
 
 static TEST_INPUT_PART_2: &str = TEST_INPUT_PART_1;
 
-fn parse(input: &str) -> Vec<(i64, Vec<i64>)> {
-    input
-        .lines()
-        .map(|x| x.split_once(": ").unwrap())
-        .map(|x| {
-            (
-                x.0.parse::<i64>().unwrap(),
-                x.1.split_ascii_whitespace()
-                    .map(|x| x.parse::<i64>().unwrap())
-                    .collect(),
-            )
-        })
-        .collect()
-}
-
-fn part_1(input: &str) -> i64 {
-    let input = parse(input);
-
-    fn can_make_number(number: i64, list: &[i64], total: i64) -> bool {
-        if let Some(this) = list.first().copied() {
-            [total + this, total * this]
-                .into_iter()
-                .any(|x| can_make_number(number, &list[1..], x))
-        } else {
-            total == number
+fn parse(input: &str) -> Result<Vec<(i64, Vec<i64>)>, ParseError> {
+    parse::lines(input, |line| {
+        let (target, numbers) = line.split_once(": ", "target: numbers")?;
+        Ok((
+            target.parse("integer")?,
+            numbers
+                .split_whitespace()
+                .map(|x| x.parse("integer"))
+                .collect::<Result<_, _>>()?,
+        ))
+    })
+}
+
+/// What the left hand operand has to be for an operator to give a result.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Left {
+    Exactly(i64),
+    /// Any left hand operand works, like for `x * 0 = 0`.
+    Anything,
+}
+
+/// A binary operator that can be placed between two numbers of an equation.
+///
+/// Equations are solved right to left, so as well as applying the operator
+/// forwards it must be able to undo itself: given the result and the right
+/// hand operand, find the left hand operand if there is one.
+trait Operator: Sync {
+    fn apply(&self, a: i64, b: i64) -> Option<i64>;
+
+    fn unapply(&self, result: i64, b: i64) -> Option<Left>;
+}
+
+struct Add;
+struct Mul;
+struct Concat;
+
+impl Operator for Add {
+    fn apply(&self, a: i64, b: i64) -> Option<i64> {
+        a.checked_add(b)
+    }
+
+    fn unapply(&self, result: i64, b: i64) -> Option<Left> {
+        result.checked_sub(b).map(Left::Exactly)
+    }
+}
+
+impl Operator for Mul {
+    fn apply(&self, a: i64, b: i64) -> Option<i64> {
+        a.checked_mul(b)
+    }
+
+    fn unapply(&self, result: i64, b: i64) -> Option<Left> {
+        // multiplying by zero loses the left hand side, so anything gives 0
+        if b == 0 {
+            return (result == 0).then_some(Left::Anything);
         }
+        if result.checked_rem(b)? != 0 {
+            return None;
+        }
+        result.checked_div(b).map(Left::Exactly)
     }
+}
 
-    input
-        .iter()
-        .filter(|(x, y)| can_make_number(*x, &y[1..], y[0]))
-        .map(|(x, _)| *x)
-        .sum()
+fn count_digits(mut n: i64) -> u32 {
+    let mut count = 0;
+    while n != 0 {
+        n /= 10;
+        count += 1;
+    }
+    count.max(1)
 }
 
-fn concat(a: i64, b: i64) -> i64 {
-    let mut n = 0;
-    let mut c = b;
-    while c != 0 {
-        n += 1;
-        c /= 10;
+impl Operator for Concat {
+    fn apply(&self, a: i64, b: i64) -> Option<i64> {
+        if a < 0 || b < 0 {
+            return None;
+        }
+        a.checked_mul(10_i64.checked_pow(count_digits(b))?)?
+            .checked_add(b)
     }
 
-    a * 10_i64.pow(n) + b
+    fn unapply(&self, result: i64, b: i64) -> Option<Left> {
+        if result < 0 || b < 0 {
+            return None;
+        }
+        let shift = 10_i64.checked_pow(count_digits(b))?;
+        if result % shift != b {
+            return None;
+        }
+        Some(Left::Exactly(result / shift))
+    }
 }
 
-#[test]
-fn check_concat() {
-    assert_eq!(concat(123, 456), 123456);
+const PART_1_OPERATORS: &[&dyn Operator] = &[&Add, &Mul];
+const PART_2_OPERATORS: &[&dyn Operator] = &[&Add, &Mul, &Concat];
+
+/// Finds operators to place between the numbers, evaluated left to right, so
+/// that they make `target`. The returned list has one fewer entry than
+/// `numbers`.
+fn solve<'a>(
+    target: i64,
+    numbers: &[i64],
+    operators: &[&'a dyn Operator],
+) -> Option<Vec<&'a dyn Operator>> {
+    fn solve_inner<'a>(
+        target: Left,
+        numbers: &[i64],
+        operators: &[&'a dyn Operator],
+        assignment: &mut Vec<&'a dyn Operator>,
+    ) -> bool {
+        let Some((&last, rest)) = numbers.split_last() else {
+            return false;
+        };
+
+        let target = match target {
+            Left::Exactly(target) => target,
+            // the numbers only have to evaluate to something, so search
+            // forwards for operators which don't overflow
+            Left::Anything => match evaluate_any(numbers, operators) {
+                Some(found) => {
+                    assignment.extend(found.into_iter().rev());
+                    return true;
+                }
+                None => return false,
+            },
+        };
+
+        if rest.is_empty() {
+            return last == target;
+        }
+
+        for &operator in operators {
+            if let Some(previous) = operator.unapply(target, last) {
+                assignment.push(operator);
+                if solve_inner(previous, rest, operators, assignment) {
+                    return true;
+                }
+                assignment.pop();
+            }
+        }
+
+        false
+    }
+
+    let mut assignment = Vec::new();
+    if solve_inner(Left::Exactly(target), numbers, operators, &mut assignment) {
+        assignment.reverse();
+        debug_assert_eq!(evaluate(numbers, &assignment), Some(target));
+        Some(assignment)
+    } else {
+        None
+    }
 }
 
-fn part_2(input: &str) -> i64 {
-    let input = parse(input);
+/// Finds any operators to place between the numbers such that evaluating
+/// them left to right doesn't overflow.
+fn evaluate_any<'a>(
+    numbers: &[i64],
+    operators: &[&'a dyn Operator],
+) -> Option<Vec<&'a dyn Operator>> {
+    fn evaluate_inner<'a>(
+        total: i64,
+        numbers: &[i64],
+        operators: &[&'a dyn Operator],
+        assignment: &mut Vec<&'a dyn Operator>,
+    ) -> bool {
+        let Some((&next, rest)) = numbers.split_first() else {
+            return true;
+        };
 
-    fn can_make_number(number: i64, list: &[i64], total: i64) -> bool {
-        if let Some(this) = list.first().copied() {
-            [total + this, total * this, concat(total, this)]
-                .into_iter()
-                .any(|x| can_make_number(number, &list[1..], x))
-        } else {
-            total == number
+        for &operator in operators {
+            if let Some(total) = operator.apply(total, next) {
+                assignment.push(operator);
+                if evaluate_inner(total, rest, operators, assignment) {
+                    return true;
+                }
+                assignment.pop();
+            }
         }
+
+        false
     }
 
-    input
-        .iter()
-        .filter(|(x, y)| can_make_number(*x, &y[1..], y[0]))
-        .map(|(x, _)| *x)
-        .sum()
+    let (&first, rest) = numbers.split_first()?;
+    let mut assignment = Vec::new();
+    evaluate_inner(first, rest, operators, &mut assignment).then_some(assignment)
+}
+
+fn evaluate(numbers: &[i64], operators: &[&dyn Operator]) -> Option<i64> {
+    let (&first, rest) = numbers.split_first()?;
+    rest.iter()
+        .zip(operators)
+        .try_fold(first, |total, (&x, operator)| operator.apply(total, x))
+}
+
+fn calibration_result(input: &str, operators: &[&dyn Operator]) -> Result<i64, ParseError> {
+    let equations = parse(input)?;
+    Ok(aoc24::maybe_par_iter!(equations, |iter| {
+        iter.filter(|(target, numbers)| solve(*target, numbers, operators).is_some())
+            .map(|(target, _)| *target)
+            .sum()
+    }))
+}
+
+fn part_1(input: &str) -> Result<i64, ParseError> {
+    calibration_result(input, PART_1_OPERATORS)
+}
+
+fn part_2(input: &str) -> Result<i64, ParseError> {
+    calibration_result(input, PART_2_OPERATORS)
 }
 
 static MY_INPUT: &str = aoc24::load_input!();
 
-fn main() {
-    println!("Part 1: {}", part_1(MY_INPUT));
-    println!("Part 2: {}", part_2(MY_INPUT));
+fn main() -> Result<(), ParseError> {
+    println!("Part 1: {}", part_1(MY_INPUT)?);
+    println!("Part 2: {}", part_2(MY_INPUT)?);
+    Ok(())
 }
 
 #[test]
-fn check_part_1() {
-    assert_eq!(part_1(TEST_INPUT_PART_1), 3749);
+fn check_concat() {
+    assert_eq!(Concat.apply(123, 456), Some(123456));
+    assert_eq!(Concat.apply(12, 0), Some(120));
+    assert_eq!(Concat.unapply(123456, 456), Some(Left::Exactly(123)));
+    assert_eq!(Concat.unapply(123456, 45), None);
+}
+
+aoc24::examples! {
+    part_1: [
+        example: TEST_INPUT_PART_1 => 3749,
+    ],
+    part_2: [
+        example: TEST_INPUT_PART_2 => 11387,
+    ],
 }
 
 #[test]
-fn check_part_2() {
-    assert_eq!(part_2(TEST_INPUT_PART_2), 11387);
+fn check_assignment() {
+    let assignment = solve(7290, &[6, 8, 6, 15], PART_2_OPERATORS).unwrap();
+    assert_eq!(assignment.len(), 3);
+    assert_eq!(evaluate(&[6, 8, 6, 15], &assignment), Some(7290));
+
+    assert!(solve(7290, &[6, 8, 6, 15], PART_1_OPERATORS).is_none());
+}
+
+#[test]
+fn check_zero_operands() {
+    // 3 * 0 + 5, where multiplying by zero forgets the 3
+    assert_eq!(part_1("5: 3 0 5\n"), Ok(5));
+    assert_eq!(part_1("0: 7 8 0\n"), Ok(0));
+    assert_eq!(part_1("1: 7 8 0\n"), Ok(0));
+    assert_eq!(part_2("9: 4 5 0 9\n"), Ok(9));
+
+    for numbers in [[3, 0, 5], [0, 0, 5], [9, 9, 0]] {
+        for target in 0..100 {
+            let brute_force = (0..4).any(|ops| {
+                let ops: Vec<&dyn Operator> =
+                    (0..2).map(|i| PART_1_OPERATORS[(ops >> i) & 1]).collect();
+                evaluate(&numbers, &ops) == Some(target)
+            });
+            assert_eq!(
+                solve(target, &numbers, PART_1_OPERATORS).is_some(),
+                brute_force
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+struct Sub;
+#[cfg(test)]
+struct Xor;
+
+#[cfg(test)]
+impl Operator for Sub {
+    fn apply(&self, a: i64, b: i64) -> Option<i64> {
+        a.checked_sub(b)
+    }
+
+    fn unapply(&self, result: i64, b: i64) -> Option<Left> {
+        result.checked_add(b).map(Left::Exactly)
+    }
+}
+
+#[cfg(test)]
+impl Operator for Xor {
+    fn apply(&self, a: i64, b: i64) -> Option<i64> {
+        Some(a ^ b)
+    }
+
+    fn unapply(&self, result: i64, b: i64) -> Option<Left> {
+        Some(Left::Exactly(result ^ b))
+    }
+}
+
+#[test]
+fn check_custom_operators() {
+    let operators: &[&dyn Operator] = &[&Sub, &Xor];
+
+    // 10 - 4 - 5 = 1, 10 - 4 ^ 5 = 3, 10 ^ 4 - 5 = 9, 10 ^ 4 ^ 5 = 11
+    for target in [1, 3, 9, 11] {
+        let assignment = solve(target, &[10, 4, 5], operators).unwrap();
+        assert_eq!(evaluate(&[10, 4, 5], &assignment), Some(target));
+    }
+    assert!(solve(2, &[10, 4, 5], operators).is_none());
 }
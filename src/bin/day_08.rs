@@ -1,6 +1,8 @@
-use std::{
-    collections::{HashMap, HashSet},
-    ops::{Add, AddAssign, Neg, Sub},
+use std::collections::{HashMap, HashSet};
+
+use aoc24::{
+    parse::{ParseError, Span},
+    Grid, Vector2d,
 };
 
 static TEST_INPUT_PART_1: &str = "............
@@ -19,172 +21,219 @@ static TEST_INPUT_PART_1: &str = "............
 
 static TEST_INPUT_PART_2: &str = TEST_INPUT_PART_1;
 
-struct Grid {
-    width: usize,
-    height: usize,
-    text: Box<[u8]>,
-}
-
-#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
-struct Vector2d(i32, i32);
-
-impl From<(i32, i32)> for Vector2d {
-    fn from(value: (i32, i32)) -> Self {
-        Vector2d(value.0, value.1)
-    }
-}
-
-impl Add for Vector2d {
-    type Output = Self;
-
-    fn add(self, rhs: Self) -> Self::Output {
-        Vector2d(self.0 + rhs.0, self.1 + rhs.1)
+fn gcd(a: i32, b: i32) -> i32 {
+    if b == 0 {
+        a.abs()
+    } else {
+        gcd(b, a % b)
     }
 }
 
-impl AddAssign for Vector2d {
-    fn add_assign(&mut self, rhs: Self) {
-        *self = *self + rhs;
-    }
+/// Which points in line with a pair of antennas are antinodes.
+enum Resonance {
+    /// Points where one antenna is exactly `k` times as far away as the other,
+    /// for each `k` in the list.
+    Ratios(Vec<i32>),
+    /// Every grid point in line with the antennas.
+    AllMultiples,
 }
 
-impl Neg for Vector2d {
-    type Output = Self;
-    fn neg(self) -> Self::Output {
-        Self(-self.0, -self.1)
-    }
-}
-
-impl Sub for Vector2d {
-    type Output = Self;
-    fn sub(self, rhs: Self) -> Self::Output {
-        self + -rhs
-    }
-}
-
-impl Grid {
-    fn new(input: &str) -> Self {
-        let width = input.lines().next().unwrap().len();
-        let height = input.lines().count();
-        Self {
-            width,
-            height,
-            text: input.as_bytes().into(),
-        }
-    }
-
-    fn get(&self, v: Vector2d) -> Option<u8> {
-        if !(0..self.width as i32).contains(&v.0) {
-            return None;
-        }
-        if !(0..self.height as i32).contains(&v.1) {
-            return None;
+/// Finds the antinodes of a pair of antennas that are on the grid.
+///
+/// Points in line with the antennas are `a + m * step` for integer `m`, where
+/// `step` is the difference between them divided down by its gcd so no grid
+/// point on the line is skipped. `b` is then at `m = g`.
+fn antinodes(grid: &Grid, a: Vector2d, b: Vector2d, resonance: &Resonance) -> Vec<Vector2d> {
+    let difference = b - a;
+    let g = gcd(difference.0, difference.1);
+    let step = Vector2d(difference.0 / g, difference.1 / g);
+    let point = |m: i32| Vector2d(a.0 + m * step.0, a.1 + m * step.1);
+
+    match resonance {
+        Resonance::Ratios(ratios) => ratios
+            .iter()
+            .flat_map(|&k| {
+                // solutions to |m| = k|m - g| and |m - g| = k|m|
+                [(k * g, k - 1), (k * g, k + 1), (-g, k - 1), (g, k + 1)]
+            })
+            .filter(|&(numerator, denominator)| denominator != 0 && numerator % denominator == 0)
+            .map(|(numerator, denominator)| point(numerator / denominator))
+            .filter(|&x| grid.get(x).is_some())
+            .collect(),
+        Resonance::AllMultiples => {
+            let forwards = (0..).map(point).take_while(|&x| grid.get(x).is_some());
+            let backwards = (1..)
+                .map(|m| point(-m))
+                .take_while(|&x| grid.get(x).is_some());
+            forwards.chain(backwards).collect()
         }
-
-        Some(self.text[(v.0 + v.1 * (self.width as i32 + 1)) as usize])
-    }
-
-    fn iter(&self) -> impl Iterator<Item = (Vector2d, u8)> + use<'_> {
-        (0..self.height as i32)
-            .flat_map(move |y| (0..self.width as i32).map(move |x| Vector2d(x, y)))
-            .map(|v| (v, self.get(v).unwrap()))
     }
 }
 
-fn part_1(input: &str) -> i64 {
-    let grid = Grid::new(input);
+fn antennas(grid: &Grid) -> HashMap<u8, Vec<Vector2d>> {
     let mut nodes: HashMap<u8, Vec<Vector2d>> = HashMap::new();
     for (position, n) in grid.iter() {
         if n != b'.' {
             nodes.entry(n).or_default().push(position);
         }
     }
+    nodes
+}
 
+fn all_antinodes(grid: &Grid, resonance: &Resonance) -> HashSet<Vector2d> {
     let mut anti_nodes = HashSet::new();
-    let mut add_anti_node = |anti_node: Vector2d| {
-        if anti_node.0 < 0
-            || anti_node.1 < 0
-            || anti_node.0 >= grid.width as i32
-            || anti_node.1 >= grid.height as i32
-        {
-            return;
-        }
-
-        anti_nodes.insert(anti_node);
-    };
 
-    for node in nodes.values() {
+    for node in antennas(grid).values() {
         for (&a, &b) in node
             .iter()
             .enumerate()
             .flat_map(|(idx, v)| node.iter().skip(idx + 1).map(move |b| (v, b)))
         {
-            let difference = b - a;
-            add_anti_node(b + difference);
-            add_anti_node(a - difference);
+            anti_nodes.extend(antinodes(grid, a, b, resonance));
         }
     }
 
-    anti_nodes.len() as i64
+    anti_nodes
 }
 
-fn part_2(input: &str) -> i64 {
-    let grid = Grid::new(input);
-    let mut nodes: HashMap<u8, Vec<Vector2d>> = HashMap::new();
-    for (position, n) in grid.iter() {
-        if n != b'.' {
-            nodes.entry(n).or_default().push(position);
+/// Draws the map with `#` on every antinode that doesn't have an antenna on
+/// it, like the puzzle does.
+fn render(grid: &Grid, resonance: &Resonance) -> String {
+    let anti_nodes = all_antinodes(grid, resonance);
+
+    let mut output = String::new();
+    for y in 0..grid.height as i32 {
+        for x in 0..grid.width as i32 {
+            let position = Vector2d(x, y);
+            let cell = grid.get(position).unwrap();
+            if cell == b'.' && anti_nodes.contains(&position) {
+                output.push('#');
+            } else {
+                output.push(cell as char);
+            }
         }
+        output.push('\n');
     }
+    output
+}
 
-    let mut anti_nodes = HashSet::new();
-    let mut add_anti_node = |anti_node: Vector2d| -> bool {
-        if anti_node.0 < 0
-            || anti_node.1 < 0
-            || anti_node.0 >= grid.width as i32
-            || anti_node.1 >= grid.height as i32
-        {
-            return false;
-        }
+fn part_1(input: &str) -> Result<i64, ParseError> {
+    let grid = Grid::parse(Span::new(input))?;
+    Ok(all_antinodes(&grid, &Resonance::Ratios(vec![2])).len() as i64)
+}
 
-        anti_nodes.insert(anti_node);
-        true
-    };
+fn part_2(input: &str) -> Result<i64, ParseError> {
+    let grid = Grid::parse(Span::new(input))?;
+    Ok(all_antinodes(&grid, &Resonance::AllMultiples).len() as i64)
+}
 
-    for node in nodes.values() {
-        for (&a, &b) in node
-            .iter()
-            .enumerate()
-            .flat_map(|(idx, v)| node.iter().skip(idx + 1).map(move |b| (v, b)))
-        {
-            let difference = b - a;
-            let mut s = a;
-            while add_anti_node(s) {
-                s += -difference;
-            }
-            let mut s = b;
-            while add_anti_node(s) {
-                s += difference;
-            }
-        }
+static MY_INPUT: &str = aoc24::load_input!();
+
+fn main() -> Result<(), ParseError> {
+    if std::env::args().any(|x| x == "--render") {
+        let grid = Grid::parse(Span::new(MY_INPUT))?;
+        println!("{}", render(&grid, &Resonance::Ratios(vec![2])));
+        println!("{}", render(&grid, &Resonance::AllMultiples));
     }
 
-    anti_nodes.len() as i64
+    println!("Part 1: {}", part_1(MY_INPUT)?);
+    println!("Part 2: {}", part_2(MY_INPUT)?);
+    Ok(())
 }
 
-static MY_INPUT: &str = aoc24::load_input!();
+aoc24::examples! {
+    part_1: [
+        example: TEST_INPUT_PART_1 => 14,
+    ],
+    part_2: [
+        example: TEST_INPUT_PART_2 => 34,
+    ],
+}
 
-fn main() {
-    println!("Part 1: {}", part_1(MY_INPUT));
-    println!("Part 2: {}", part_2(MY_INPUT));
+#[test]
+fn check_render() {
+    let grid = Grid::new(TEST_INPUT_PART_1);
+    assert_eq!(
+        render(&grid, &Resonance::Ratios(vec![2])),
+        "......#....#
+...#....0...
+....#0....#.
+..#....0....
+....0....#..
+.#....A.....
+...#........
+#......#....
+........A...
+.........A..
+..........#.
+..........#.
+"
+    );
+
+    let grid = Grid::new(
+        "T.........
+...T......
+.T........
+..........
+..........
+..........
+..........
+..........
+..........
+..........
+",
+    );
+    assert_eq!(
+        render(&grid, &Resonance::AllMultiples),
+        "T....#....
+...T......
+.T....#...
+.........#
+..#.......
+..........
+...#......
+..........
+....#.....
+..........
+"
+    );
+    assert_eq!(all_antinodes(&grid, &Resonance::AllMultiples).len(), 9);
 }
 
 #[test]
-fn check_part_1() {
-    assert_eq!(part_1(TEST_INPUT_PART_1), 14);
+fn check_non_primitive_difference() {
+    let grid = Grid::new(".......\n.......\n.......\n.......\n.......\n.......\n.......\n");
+
+    // the antennas are 3 apart, so there are antinodes a third of the way
+    // between them as well as outside them
+    let mut nodes = antinodes(
+        &grid,
+        Vector2d(0, 0),
+        Vector2d(3, 3),
+        &Resonance::Ratios(vec![2]),
+    );
+    nodes.sort_by_key(|x| x.0);
+    assert_eq!(nodes, [Vector2d(1, 1), Vector2d(2, 2), Vector2d(6, 6)]);
+
+    let nodes = antinodes(
+        &grid,
+        Vector2d(0, 0),
+        Vector2d(2, 4),
+        &Resonance::AllMultiples,
+    );
+    assert_eq!(
+        nodes,
+        [
+            Vector2d(0, 0),
+            Vector2d(1, 2),
+            Vector2d(2, 4),
+            Vector2d(3, 6)
+        ]
+    );
 }
 
 #[test]
-fn check_part_2() {
-    assert_eq!(part_2(TEST_INPUT_PART_2), 34);
+fn check_parse_errors() {
+    let error = part_1("...\n.a.\n..\n").unwrap_err();
+    assert_eq!(error.to_string(), "line 3: expected `a row 3 wide`");
 }
@@ -1,12 +1,17 @@
-use std::collections::VecDeque;
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+use aoc24::parse::{ParseError, Span};
 
-use itertools::Itertools;
+#[cfg(test)]
+use std::collections::VecDeque;
 
 static TEST_INPUT_PART_1: &str = "2333133121414131402";
 static TEST_INPUT_PART_2: &str = TEST_INPUT_PART_1;
 
+#[cfg(test)]
 const SENTINEL: i64 = i64::MAX;
 
+#[cfg(test)]
 fn expand(input: &str) -> VecDeque<i64> {
     let input = input.trim();
     let mut expanded = Vec::new();
@@ -27,6 +32,7 @@ fn expand(input: &str) -> VecDeque<i64> {
     expanded.into()
 }
 
+#[cfg(test)]
 fn back(a: &mut VecDeque<i64>, len: usize) -> i64 {
     if a.len() - 1 == len {
         return SENTINEL;
@@ -38,6 +44,7 @@ fn back(a: &mut VecDeque<i64>, len: usize) -> i64 {
     b
 }
 
+#[cfg(test)]
 fn contract(input: &mut VecDeque<i64>) {
     let mut idx = 0;
     while idx < input.len() {
@@ -49,7 +56,8 @@ fn contract(input: &mut VecDeque<i64>) {
     }
 }
 
-fn part_1(input: &str) -> i64 {
+#[cfg(test)]
+fn checksum_expanded(input: &str) -> i64 {
     let mut v = expand(input);
     contract(&mut v);
 
@@ -61,6 +69,75 @@ fn part_1(input: &str) -> i64 {
         .sum()
 }
 
+/// The size of each file and gap in the disk map, alternating and starting
+/// with a file.
+fn parse(input: &str) -> Result<Vec<usize>, ParseError> {
+    let map = Span::new(input).trim();
+    map.text
+        .char_indices()
+        .map(|(i, x)| {
+            x.to_digit(10)
+                .map(|x| x as usize)
+                .ok_or_else(|| map.skip(i).error("a digit"))
+        })
+        .collect()
+}
+
+fn sum_of_positions(start: usize, len: usize) -> i64 {
+    (len * (2 * start + len).saturating_sub(1) / 2) as i64
+}
+
+/// Computes the part 1 checksum straight from the disk map without expanding
+/// it into blocks. One cursor walks forwards through the map laying out files
+/// and filling gaps, the other walks backwards through the files that are
+/// used to fill those gaps.
+fn checksum_streaming(sizes: &[usize]) -> i64 {
+    if sizes.is_empty() {
+        return 0;
+    }
+    let size = |idx: usize| sizes[idx];
+
+    let mut left = 0;
+    let mut right = (sizes.len() - 1) & !1;
+    let mut right_remaining = size(right);
+
+    let mut position = 0;
+    let mut checksum = 0;
+
+    while left < right {
+        if left % 2 == 0 {
+            let len = size(left);
+            checksum += (left / 2) as i64 * sum_of_positions(position, len);
+            position += len;
+        } else {
+            let mut gap = size(left);
+            while gap != 0 && left < right {
+                let len = gap.min(right_remaining);
+                checksum += (right / 2) as i64 * sum_of_positions(position, len);
+                position += len;
+                gap -= len;
+                right_remaining -= len;
+
+                if right_remaining == 0 {
+                    right -= 2;
+                    right_remaining = size(right);
+                }
+            }
+        }
+        left += 1;
+    }
+
+    if left == right {
+        checksum += (right / 2) as i64 * sum_of_positions(position, right_remaining);
+    }
+
+    checksum
+}
+
+fn part_1(input: &str) -> Result<i64, ParseError> {
+    Ok(checksum_streaming(&parse(input)?))
+}
+
 #[derive(Debug, Clone)]
 struct File {
     id: i64,
@@ -79,8 +156,7 @@ struct Disk {
 }
 
 impl Disk {
-    fn new(input: &str) -> Self {
-        let input = input.trim();
+    fn new(sizes: &[usize]) -> Self {
         let mut files = Vec::new();
         let mut gaps = Vec::new();
         let mut a = true;
@@ -88,10 +164,7 @@ impl Disk {
 
         let mut idx = 0;
 
-        gaps.push(Gap { idx: 0, size: 0 });
-
-        for &x in input.as_bytes() {
-            let number = (x - b'0') as usize;
+        for &number in sizes {
             if a {
                 files.push(File {
                     id: v,
@@ -99,60 +172,163 @@ impl Disk {
                     idx,
                 });
                 v += 1;
-            } else {
+            } else if number != 0 {
                 gaps.push(Gap { idx, size: number })
             }
             idx += number;
             a = !a;
         }
 
-        gaps.push(Gap { idx, size: 0 });
-
         Self { files, gaps }
     }
-}
 
-fn part_2(input: &str) -> i64 {
-    let mut disk = Disk::new(input);
+    /// Moves every file, highest id first, into the leftmost gap that can
+    /// hold it. `on_move` is called with the disk after each file that moves.
+    ///
+    /// Gaps are kept in a min-heap per size, so finding the leftmost gap that
+    /// fits is a look at the top of at most 9 heaps.
+    fn compact(&mut self, mut on_move: impl FnMut(&Disk)) {
+        let mut gaps_by_size: [BinaryHeap<Reverse<usize>>; 10] = Default::default();
+        for gap in &self.gaps {
+            gaps_by_size[gap.size].push(Reverse(gap.idx));
+        }
 
-    let mut unprocessed = disk.files.clone();
-    let mut destination = unprocessed.clone();
+        for file_idx in (0..self.files.len()).rev() {
+            let file = &self.files[file_idx];
 
-    while let Some(mut end) = unprocessed.pop() {
-        if let Some((slot, _)) = destination
-            .iter()
-            .tuple_windows()
-            .find(|(a, b)| b.idx - (a.idx + a.size) >= end.size)
-        {
-            if slot.idx >= end.idx {
+            let Some((size, Reverse(gap_idx))) = (file.size..gaps_by_size.len())
+                .filter_map(|size| gaps_by_size[size].peek().map(|&idx| (size, idx)))
+                .min_by_key(|&(_, Reverse(idx))| idx)
+            else {
+                continue;
+            };
+
+            if gap_idx >= file.idx {
                 continue;
             }
-            end.idx = slot.idx + slot.size;
-            destination.retain(|x| x.id != end.id);
-            destination.push(end);
-            destination.sort_by_key(|x| x.idx);
+
+            gaps_by_size[size].pop();
+            let remaining = size - file.size;
+            if remaining != 0 {
+                gaps_by_size[remaining].push(Reverse(gap_idx + file.size));
+            }
+
+            // nothing to the left of this file will ever fit here, but keep
+            // track of it so the final list of gaps is complete
+            gaps_by_size[file.size].push(Reverse(file.idx));
+
+            self.files[file_idx].idx = gap_idx;
+            on_move(self);
         }
+
+        self.gaps = gaps_by_size
+            .into_iter()
+            .enumerate()
+            .flat_map(|(size, heap)| heap.into_iter().map(move |Reverse(idx)| Gap { idx, size }))
+            .collect();
+        self.gaps.sort_by_key(|gap| gap.idx);
     }
 
-    destination
-        .iter()
-        .map(|x| (x.idx..(x.idx + x.size)).sum::<usize>() as i64 * x.id)
-        .sum()
+    fn checksum(&self) -> i64 {
+        self.files
+            .iter()
+            .map(|x| (x.idx..(x.idx + x.size)).sum::<usize>() as i64 * x.id)
+            .sum()
+    }
+
+    /// Draws the disk the way the puzzle does, with one character per block
+    /// and `.` for free space. Ids above 9 only show their last digit.
+    fn render(&self) -> String {
+        let len = self.files.iter().map(|file| file.size).sum::<usize>()
+            + self.gaps.iter().map(|gap| gap.size).sum::<usize>();
+
+        let mut blocks = vec![b'.'; len];
+        for file in &self.files {
+            blocks[file.idx..file.idx + file.size].fill(b'0' + (file.id % 10) as u8);
+        }
+        String::from_utf8(blocks).unwrap()
+    }
+}
+
+/// Calls `on_step` with the disk drawn before compacting and after every
+/// file that moves.
+fn render_compaction(input: &str, mut on_step: impl FnMut(String)) -> Result<(), ParseError> {
+    let mut disk = Disk::new(&parse(input)?);
+    on_step(disk.render());
+    disk.compact(|disk| on_step(disk.render()));
+    Ok(())
+}
+
+fn part_2(input: &str) -> Result<i64, ParseError> {
+    let mut disk = Disk::new(&parse(input)?);
+    disk.compact(|_| {});
+    Ok(disk.checksum())
 }
 
 static MY_INPUT: &str = aoc24::load_input!();
 
-fn main() {
-    println!("Part 1: {}", part_1(MY_INPUT));
-    println!("Part 2: {}", part_2(MY_INPUT));
+fn main() -> Result<(), ParseError> {
+    if std::env::args().any(|x| x == "--render") {
+        render_compaction(MY_INPUT, |step| println!("{step}"))?;
+    }
+
+    println!("Part 1: {}", part_1(MY_INPUT)?);
+    println!("Part 2: {}", part_2(MY_INPUT)?);
+    Ok(())
+}
+
+aoc24::examples! {
+    part_1: [
+        example: TEST_INPUT_PART_1 => 1928,
+    ],
+    part_2: [
+        example: TEST_INPUT_PART_2 => 2858,
+    ],
+}
+
+#[test]
+fn check_streaming_matches_expanded() {
+    assert_eq!(checksum_expanded(TEST_INPUT_PART_1), 1928);
+
+    let mut state: u64 = 0x2024_0909;
+    for len in [1, 2, 3, 4, 5, 10, 11, 100, 999, 1000] {
+        let map: String = (0..len)
+            .map(|_| {
+                state = state
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                (b'0' + (state >> 33) as u8 % 10) as char
+            })
+            .collect();
+
+        assert_eq!(part_1(&map), Ok(checksum_expanded(&map)), "{map}");
+    }
+
+    for map in ["0", "1", "10", "101", "19", "91", "000", "1010101"] {
+        assert_eq!(part_1(map), Ok(checksum_expanded(map)), "{map}");
+    }
 }
 
 #[test]
-fn check_part_1() {
-    assert_eq!(part_1(TEST_INPUT_PART_1), 1928);
+fn check_parse_errors() {
+    let error = part_2("123x5\n").unwrap_err();
+    assert_eq!((error.line, error.column), (1, 4));
+    assert_eq!(error.to_string(), "line 1: expected `a digit`");
 }
 
 #[test]
-fn check_part_2() {
-    assert_eq!(part_2(TEST_INPUT_PART_2), 2858);
+fn check_compact_steps() {
+    let mut steps = Vec::new();
+    render_compaction(TEST_INPUT_PART_2, |step| steps.push(step)).unwrap();
+
+    assert_eq!(
+        steps,
+        [
+            "00...111...2...333.44.5555.6666.777.888899",
+            "0099.111...2...333.44.5555.6666.777.8888..",
+            "0099.1117772...333.44.5555.6666.....8888..",
+            "0099.111777244.333....5555.6666.....8888..",
+            "00992111777.44.333....5555.6666.....8888..",
+        ]
+    );
 }
@@ -1,7 +1,8 @@
-use std::{
-    collections::HashSet,
-    ops::{Add, AddAssign, Neg, Sub},
+use aoc24::{
+    parse::{ParseError, Span},
+    Grid, Vector2d,
 };
+use fixedbitset::FixedBitSet;
 
 static TEST_INPUT_PART_1: &str = "89010123
 78121874
@@ -15,151 +16,174 @@ static TEST_INPUT_PART_1: &str = "89010123
 
 static TEST_INPUT_PART_2: &str = TEST_INPUT_PART_1;
 
-struct Grid {
-    width: usize,
-    height: usize,
-    text: Box<[u8]>,
+/// The scores and ratings of every cell on the map, found by working down
+/// from the peaks one height at a time.
+struct TrailMap {
+    grid: Grid,
+    /// For each cell, which peaks (by index) can be reached by a hiking trail
+    /// starting there.
+    reachable: Vec<FixedBitSet>,
+    /// For each cell, how many distinct hiking trails start there.
+    ratings: Vec<i64>,
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
-struct Vector2d(i32, i32);
+impl TrailMap {
+    fn new(input: &str) -> Result<Self, ParseError> {
+        let grid = Grid::parse(Span::new(input))?;
 
-impl From<(i32, i32)> for Vector2d {
-    fn from(value: (i32, i32)) -> Self {
-        Vector2d(value.0, value.1)
-    }
-}
+        let mut by_height: [Vec<Vector2d>; 10] = Default::default();
+        for (position, x) in grid.iter() {
+            if x.is_ascii_digit() {
+                by_height[(x - b'0') as usize].push(position);
+            }
+        }
 
-impl Add for Vector2d {
-    type Output = Self;
+        let peaks = by_height[9].len();
+        let mut reachable = vec![FixedBitSet::with_capacity(peaks); grid.width * grid.height];
+        let mut ratings = vec![0; grid.width * grid.height];
+        let index = |v: Vector2d| v.0 as usize + v.1 as usize * grid.width;
 
-    fn add(self, rhs: Self) -> Self::Output {
-        Vector2d(self.0 + rhs.0, self.1 + rhs.1)
-    }
-}
+        for (peak, &position) in by_height[9].iter().enumerate() {
+            reachable[index(position)].insert(peak);
+            ratings[index(position)] = 1;
+        }
 
-impl AddAssign for Vector2d {
-    fn add_assign(&mut self, rhs: Self) {
-        *self = *self + rhs;
-    }
-}
+        for height in (0..9).rev() {
+            for &position in &by_height[height] {
+                let next = b'0' + height as u8 + 1;
+                let mut peaks = FixedBitSet::with_capacity(peaks);
+                let mut rating = 0;
+
+                for neighbour in position.neighbours() {
+                    if grid.get(neighbour) == Some(next) {
+                        peaks.union_with(&reachable[index(neighbour)]);
+                        rating += ratings[index(neighbour)];
+                    }
+                }
+
+                reachable[index(position)] = peaks;
+                ratings[index(position)] = rating;
+            }
+        }
 
-impl Neg for Vector2d {
-    type Output = Self;
-    fn neg(self) -> Self::Output {
-        Self(-self.0, -self.1)
+        Ok(Self {
+            grid,
+            reachable,
+            ratings,
+        })
     }
-}
 
-impl Sub for Vector2d {
-    type Output = Self;
-    fn sub(self, rhs: Self) -> Self::Output {
-        self + -rhs
+    fn index(&self, position: Vector2d) -> usize {
+        position.0 as usize + position.1 as usize * self.grid.width
     }
-}
 
-impl Grid {
-    fn new(input: &str) -> Self {
-        let width = input.lines().next().unwrap().len();
-        let height = input.lines().count();
-        Self {
-            width,
-            height,
-            text: input.as_bytes().into(),
-        }
+    fn trailheads(&self) -> impl Iterator<Item = Vector2d> + use<'_> {
+        self.grid.iter().filter(|&(_, x)| x == b'0').map(|(v, _)| v)
     }
 
-    fn get(&self, v: Vector2d) -> Option<u8> {
-        if !(0..self.width as i32).contains(&v.0) {
-            return None;
-        }
-        if !(0..self.height as i32).contains(&v.1) {
-            return None;
-        }
-
-        Some(self.text[(v.0 + v.1 * (self.width as i32 + 1)) as usize])
+    /// How many peaks can be reached from `position`.
+    fn score(&self, position: Vector2d) -> usize {
+        self.reachable[self.index(position)].count_ones(..)
     }
 
-    fn iter(&self) -> impl Iterator<Item = (Vector2d, u8)> + use<'_> {
-        (0..self.height as i32)
-            .flat_map(move |y| (0..self.width as i32).map(move |x| Vector2d(x, y)))
-            .map(|v| (v, self.get(v).unwrap()))
+    /// How many distinct hiking trails start at `position`.
+    fn rating(&self, position: Vector2d) -> i64 {
+        self.ratings[self.index(position)]
     }
-}
-
-fn part_1(input: &str) -> i64 {
-    fn count_paths(grid: &Grid, position: Vector2d, depth: u8, reached: &mut HashSet<Vector2d>) {
-        let Some(x) = grid.get(position) else {
-            return;
-        };
-
-        if x != depth {
-            return;
-        }
 
-        if x == b'9' {
-            reached.insert(position);
-            return;
-        }
+    fn score_grid(&self) -> Vec<Vec<usize>> {
+        (0..self.grid.height as i32)
+            .map(|y| {
+                (0..self.grid.width as i32)
+                    .map(|x| self.score(Vector2d(x, y)))
+                    .collect()
+            })
+            .collect()
+    }
 
-        [(1, 0), (0, -1), (-1, 0), (0, 1)]
-            .into_iter()
-            .map(|(x, y)| Vector2d(x, y))
-            .for_each(|x| count_paths(grid, x + position, depth + 1, reached))
+    fn rating_grid(&self) -> Vec<Vec<i64>> {
+        (0..self.grid.height as i32)
+            .map(|y| {
+                (0..self.grid.width as i32)
+                    .map(|x| self.rating(Vector2d(x, y)))
+                    .collect()
+            })
+            .collect()
     }
+}
 
-    let grid = Grid::new(input);
-    grid.iter()
-        .filter(|(_, x)| *x == b'0')
-        .map(|(v, _)| {
-            let mut s = HashSet::new();
-            count_paths(&grid, v, b'0', &mut s);
-            s.len() as i64
-        })
-        .sum()
+fn part_1(input: &str) -> Result<i64, ParseError> {
+    let map = TrailMap::new(input)?;
+    Ok(map.trailheads().map(|x| map.score(x) as i64).sum())
 }
 
-fn part_2(input: &str) -> i64 {
-    fn count_paths(grid: &Grid, position: Vector2d, depth: u8) -> i64 {
-        let Some(x) = grid.get(position) else {
-            return 0;
-        };
+fn part_2(input: &str) -> Result<i64, ParseError> {
+    let map = TrailMap::new(input)?;
+    Ok(map.trailheads().map(|x| map.rating(x)).sum())
+}
 
-        if x != depth {
-            return 0;
-        }
+static MY_INPUT: &str = aoc24::load_input!();
 
-        if x == b'9' {
-            return 1;
+fn main() -> Result<(), ParseError> {
+    if std::env::args().any(|x| x == "--grid") {
+        let map = TrailMap::new(MY_INPUT)?;
+        for (scores, ratings) in map.score_grid().iter().zip(map.rating_grid()) {
+            println!("{:?} {:?}", scores, ratings);
         }
-
-        [(1, 0), (0, -1), (-1, 0), (0, 1)]
-            .into_iter()
-            .map(|(x, y)| Vector2d(x, y))
-            .map(|x| count_paths(grid, x + position, depth + 1))
-            .sum()
     }
 
-    let grid = Grid::new(input);
-    grid.iter()
-        .filter(|(_, x)| *x == b'0')
-        .map(|(v, _)| count_paths(&grid, v, b'0'))
-        .sum()
+    println!("Part 1: {}", part_1(MY_INPUT)?);
+    println!("Part 2: {}", part_2(MY_INPUT)?);
+    Ok(())
 }
 
-static MY_INPUT: &str = aoc24::load_input!();
-
-fn main() {
-    println!("Part 1: {}", part_1(MY_INPUT));
-    println!("Part 2: {}", part_2(MY_INPUT));
+aoc24::examples! {
+    part_1: [
+        example: TEST_INPUT_PART_1 => 36,
+    ],
+    part_2: [
+        example: TEST_INPUT_PART_2 => 81,
+    ],
 }
 
 #[test]
-fn check_part_1() {
-    assert_eq!(part_1(TEST_INPUT_PART_1), 36);
+fn check_grids() {
+    let map = TrailMap::new(
+        "0123
+1234
+8765
+9876
+",
+    )
+    .unwrap();
+    assert_eq!(map.score(Vector2d(0, 0)), 1);
+    assert_eq!(map.rating(Vector2d(0, 0)), 16);
+    assert_eq!(
+        map.score_grid(),
+        [[1, 1, 1, 1], [1, 1, 1, 1], [1, 1, 1, 1], [1, 1, 1, 1]]
+    );
+    assert_eq!(
+        map.rating_grid(),
+        [[16, 12, 8, 4], [4, 4, 4, 4], [1, 2, 3, 4], [1, 1, 1, 1]]
+    );
+
+    let map = TrailMap::new(
+        ".....0.
+..4321.
+..5..2.
+..6543.
+..7..4.
+..8765.
+..9....
+",
+    )
+    .unwrap();
+    assert_eq!(map.rating(Vector2d(5, 0)), 3);
+    assert_eq!(map.score(Vector2d(5, 0)), 1);
 }
 
 #[test]
-fn check_part_2() {
-    assert_eq!(part_2(TEST_INPUT_PART_2), 81);
+fn check_parse_errors() {
+    let error = part_2("0123\n1234\n876\n").unwrap_err();
+    assert_eq!(error.to_string(), "line 3: expected `a row 4 wide`");
 }
@@ -1,72 +1,143 @@
 use std::collections::HashMap;
 
+use aoc24::parse::{ParseError, Span};
+use num_bigint::BigUint;
+
 static TEST_INPUT_PART_1: &str = "125 17";
+static TEST_INPUT_PART_2: &str = TEST_INPUT_PART_1;
 
-fn count_digits(mut n: i64) -> i64 {
-    let mut count = 0;
-    while n != 0 {
-        n /= 10;
-        count += 1;
+fn split_digits(n: &BigUint) -> Option<(BigUint, BigUint)> {
+    let number_of_digits = n.to_string().len() as u32;
+    if !number_of_digits.is_multiple_of(2) {
+        return None;
     }
-    count
+
+    let half = BigUint::from(10_u32).pow(number_of_digits / 2);
+    Some((n / &half, n % &half))
 }
 
-fn split_digits(mut n: i64) -> Option<(i64, i64)> {
-    let number_of_digits = count_digits(n);
-    if number_of_digits % 2 != 0 {
-        return None;
+/// A single rule for what happens to a stone when you blink. The first rule
+/// in the list that matches a stone is the one that applies. Stones can grow
+/// without limit, so their values are big integers.
+enum Rule {
+    Replace { from: u64, to: u64 },
+    SplitEvenDigits,
+    Multiply(u64),
+}
+
+impl Rule {
+    fn apply(&self, stone: &BigUint) -> Option<Stones> {
+        match *self {
+            Rule::Replace { from, to } if *stone == from.into() => Some(Stones::One(to.into())),
+            Rule::Replace { .. } => None,
+            Rule::SplitEvenDigits => split_digits(stone).map(|(l, r)| Stones::Two(l, r)),
+            Rule::Multiply(factor) => Some(Stones::One(stone * factor)),
+        }
     }
+}
+
+enum Stones {
+    One(BigUint),
+    Two(BigUint, BigUint),
+}
+
+static PUZZLE_RULES: &[Rule] = &[
+    Rule::Replace { from: 0, to: 1 },
+    Rule::SplitEvenDigits,
+    Rule::Multiply(2024),
+];
+
+fn transform(stone: &BigUint, rules: &[Rule]) -> Stones {
+    rules
+        .iter()
+        .find_map(|rule| rule.apply(stone))
+        .unwrap_or_else(|| Stones::One(stone.clone()))
+}
 
-    let mut t = 0;
-    for x in 0..number_of_digits / 2 {
-        t += (n % 10) * 10_i64.pow(x as u32);
-        n /= 10;
+fn blink(
+    stones: &HashMap<BigUint, BigUint>,
+    rules: &[Rule],
+    cache: &mut HashMap<BigUint, Stones>,
+) -> HashMap<BigUint, BigUint> {
+    let mut next: HashMap<BigUint, BigUint> = HashMap::new();
+
+    for (stone, count) in stones {
+        match cache
+            .entry(stone.clone())
+            .or_insert_with(|| transform(stone, rules))
+        {
+            Stones::One(a) => *next.entry(a.clone()).or_default() += count,
+            Stones::Two(a, b) => {
+                *next.entry(a.clone()).or_default() += count;
+                *next.entry(b.clone()).or_default() += count;
+            }
+        }
     }
 
-    Some((n, t))
+    next
 }
 
-fn transform_cached(x: i64, remaining_steps: i64, cache: &mut HashMap<(i64, i64), i64>) -> i64 {
-    if let Some(left) = cache.get(&(x, remaining_steps)) {
-        return *left;
+fn solve_with_rules(input: &str, steps: usize, rules: &[Rule]) -> Result<BigUint, ParseError> {
+    let mut stones: HashMap<BigUint, BigUint> = HashMap::new();
+    for stone in Span::new(input).split_whitespace() {
+        *stones.entry(stone.parse("integer")?).or_default() += 1_u32;
     }
 
-    if remaining_steps == 0 {
-        return 1;
+    let mut cache = HashMap::new();
+    for _ in 0..steps {
+        stones = blink(&stones, rules, &mut cache);
     }
 
-    let result = if x == 0 {
-        transform_cached(1, remaining_steps - 1, cache)
-    } else if let Some((l, r)) = split_digits(x) {
-        transform_cached(l, remaining_steps - 1, cache)
-            + transform_cached(r, remaining_steps - 1, cache)
-    } else {
-        transform_cached(x * 2024, remaining_steps - 1, cache)
-    };
+    Ok(stones.values().sum())
+}
+
+fn solve(input: &str, steps: usize) -> Result<BigUint, ParseError> {
+    solve_with_rules(input, steps, PUZZLE_RULES)
+}
 
-    cache.insert((x, remaining_steps), result);
+static MY_INPUT: &str = aoc24::load_input!();
 
-    result
+fn main() -> Result<(), ParseError> {
+    println!("Part 1: {}", solve(MY_INPUT, 25)?);
+    println!("Part 2: {}", solve(MY_INPUT, 75)?);
+    Ok(())
 }
 
-fn solve(input: &str, steps: i64) -> i64 {
-    let mut cache = HashMap::new();
+#[test]
+fn check_split_digits() {
+    let split = |n: u32| split_digits(&n.into());
+    assert_eq!(split(1000), Some((10_u32.into(), 0_u32.into())));
+    assert_eq!(split(253000), Some((253_u32.into(), 0_u32.into())));
+    assert_eq!(split(512), None);
+    assert_eq!(split(0), None);
+}
 
-    input
-        .split_ascii_whitespace()
-        .map(|x| x.parse::<i64>().unwrap())
-        .map(|x| transform_cached(x, steps, &mut cache))
-        .sum()
+aoc24::examples! {
+    solve: [
+        six_blinks: TEST_INPUT_PART_1, 6 => 22,
+        part_1: TEST_INPUT_PART_1, 25 => 55312,
+        part_2: TEST_INPUT_PART_2, 75 => 65601038650482_u64,
+    ],
 }
 
-static MY_INPUT: &str = aoc24::load_input!();
+#[test]
+fn check_many_steps() {
+    let count = solve(TEST_INPUT_PART_1, 2000).unwrap();
+    assert!(count > u128::MAX.into());
 
-fn main() {
-    println!("Part 1: {}", solve(MY_INPUT, 25));
-    println!("Part 1: {}", solve(MY_INPUT, 75));
+    let error = solve("125 17\n1x\n", 1).unwrap_err();
+    assert_eq!((error.line, error.column), (2, 1));
 }
 
 #[test]
-fn check_part_1() {
-    assert_eq!(solve(TEST_INPUT_PART_1, 25), 55312);
+fn check_custom_rules() {
+    let rules = [Rule::SplitEvenDigits, Rule::Multiply(10)];
+    // 1 -> 10 -> 1 0 -> 10 0 -> 1 0 0 ...
+    assert_eq!(solve_with_rules("1", 4, &rules), Ok(3_u32.into()));
+
+    // 2024^50 is far past u64, and each multiplication keeps a single stone
+    assert_eq!(
+        solve_with_rules("1", 50, &[Rule::Multiply(2024)]),
+        Ok(1_u32.into())
+    );
 }
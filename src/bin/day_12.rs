@@ -1,6 +1,9 @@
 use std::collections::HashSet;
 
-use aoc24::{Grid, Vector2d};
+use aoc24::{
+    parse::{ParseError, Span},
+    Grid, Vector2d,
+};
 
 static TEST_INPUT_PART_1: &str = "RRRRIICCFF
 RRRRIICCCF
@@ -21,132 +24,179 @@ EXXXX
 EEEEE
 ";
 
-fn fill(grid: &Grid, letter: u8, position: Vector2d, pos: &mut HashSet<Vector2d>) {
-    if grid.get(position) != Some(letter) {
-        return;
-    }
-    if pos.contains(&position) {
-        return;
-    }
-
-    pos.insert(position);
-
-    for position in position.neighbours() {
-        fill(grid, letter, position, pos);
-    }
+fn part_1(input: &str) -> Result<i64, ParseError> {
+    let grid = Grid::parse(Span::new(input))?;
+    Ok(grid
+        .regions()
+        .map(|region| (region.area() * region.perimeter) as i64)
+        .sum())
 }
 
-fn perimeter(area: &HashSet<Vector2d>) -> i64 {
-    let mut perimeter = 0;
-    for position in area.iter() {
-        perimeter += position.neighbours().filter(|x| !area.contains(x)).count() as i64;
-    }
-    perimeter
+fn part_2(input: &str) -> Result<i64, ParseError> {
+    let grid = Grid::parse(Span::new(input))?;
+    Ok(grid
+        .regions()
+        .map(|region| (region.area() * region.sides) as i64)
+        .sum())
 }
 
-fn explore(
-    area: &HashSet<Vector2d>,
-    mut position: Vector2d,
-    direction: Vector2d,
-    look: Vector2d,
-    explored: &mut HashSet<(Vector2d, Vector2d)>,
-) -> i64 {
-    let mut count = 0;
-    loop {
-        explored.insert((position, look));
-        if area.contains(&position) || !area.contains(&(position + look)) {
-            break;
+/// The terminal escape code for the background of the `index`th colour. The
+/// first few come from a palette that reads well; after that every index gets
+/// its own 24-bit colour, since greedy colouring can need as many colours as
+/// a region has neighbours.
+fn background(index: usize) -> String {
+    const PALETTE: [u8; 8] = [160, 34, 26, 178, 129, 37, 208, 245];
+
+    match PALETTE.get(index) {
+        Some(colour) => format!("\x1b[48;5;{colour}m"),
+        None => {
+            // multiplying by an odd number is a bijection modulo 2^24
+            let rgb = (index as u32).wrapping_mul(0x9e37_79b1) & 0xff_ffff;
+            let [_, r, g, b] = rgb.to_be_bytes();
+            format!("\x1b[48;2;{r};{g};{b}m")
         }
-        position += direction;
-        count += 1;
     }
-
-    count
 }
 
-fn sides(area: &HashSet<Vector2d>) -> i64 {
-    let mut processed: HashSet<(Vector2d, Vector2d)> = HashSet::new();
+/// Draws the map for a terminal with each region given a background colour
+/// that differs from the regions next to it.
+fn render(grid: &Grid) -> String {
+    let regions: Vec<_> = grid.regions().collect();
 
-    let mut side_lengths = 0;
-
-    for &position in area.iter() {
-        for direction in Vector2d::DIRECTIONS {
-            let neighbour = position + direction;
-            if area.contains(&neighbour) {
-                continue;
-            }
-            let look = -direction;
-            if processed.contains(&(neighbour, look)) {
-                continue;
-            }
-
-            explore(area, neighbour, direction.rotate(), look, &mut processed);
-            explore(area, neighbour, -(direction.rotate()), look, &mut processed);
-
-            side_lengths += 1;
+    let mut labels = vec![0; grid.width * grid.height];
+    for (label, region) in regions.iter().enumerate() {
+        for &cell in &region.cells {
+            labels[cell.0 as usize + cell.1 as usize * grid.width] = label;
         }
     }
+    let label = |v: Vector2d| {
+        grid.get(v)
+            .map(|_| labels[v.0 as usize + v.1 as usize * grid.width])
+    };
+
+    let mut colours: Vec<usize> = Vec::with_capacity(regions.len());
+    for (idx, region) in regions.iter().enumerate() {
+        let taken: HashSet<usize> = region
+            .cells
+            .iter()
+            .flat_map(|cell| cell.neighbours())
+            .filter_map(label)
+            .filter(|&x| x < idx)
+            .map(|x| colours[x])
+            .collect();
+        colours.push((0..).find(|x| !taken.contains(x)).unwrap());
+    }
 
-    side_lengths
-}
-
-fn part_1(input: &str) -> i64 {
-    let grid = Grid::new(input);
-
-    let mut sum = 0;
-
-    let mut processed: HashSet<Vector2d> = HashSet::new();
-    for (position, kind) in grid.iter() {
-        if processed.contains(&position) {
-            continue;
+    let mut output = String::new();
+    for y in 0..grid.height as i32 {
+        for x in 0..grid.width as i32 {
+            let position = Vector2d(x, y);
+            output.push_str(&background(colours[label(position).unwrap()]));
+            output.push(grid.get(position).unwrap() as char);
         }
-
-        let mut area = HashSet::new();
-        fill(&grid, kind, position, &mut area);
-
-        processed.extend(area.iter().copied());
-
-        sum += area.len() as i64 * perimeter(&area);
+        output.push_str("\x1b[0m\n");
     }
-
-    sum
+    output
 }
 
-fn part_2(input: &str) -> i64 {
-    let grid = Grid::new(input);
-
-    let mut sum = 0;
-
-    let mut processed: HashSet<Vector2d> = HashSet::new();
-    for (position, kind) in grid.iter() {
-        if processed.contains(&position) {
-            continue;
-        }
-
-        let mut area = HashSet::new();
-        fill(&grid, kind, position, &mut area);
-
-        processed.extend(area.iter().copied());
+static MY_INPUT: &str = aoc24::load_input!();
 
-        sum += area.len() as i64 * sides(&area);
+fn main() -> Result<(), ParseError> {
+    if std::env::args().any(|x| x == "--render") {
+        print!("{}", render(&Grid::parse(Span::new(MY_INPUT))?));
     }
 
-    sum
+    println!("Part 1: {}", part_1(MY_INPUT)?);
+    println!("Part 2: {}", part_2(MY_INPUT)?);
+    Ok(())
 }
 
-static MY_INPUT: &str = aoc24::load_input!();
+aoc24::examples! {
+    part_1: [
+        example: TEST_INPUT_PART_1 => 1930,
+    ],
+    part_2: [
+        example: TEST_INPUT_PART_2 => 236,
+    ],
+}
 
-fn main() {
-    println!("Part 1: {}", part_1(MY_INPUT));
-    println!("Part 2: {}", part_2(MY_INPUT));
+#[test]
+fn check_regions() {
+    let grid = Grid::new(
+        "AAAA
+BBCD
+BBCC
+EEEC
+",
+    );
+    let regions: Vec<_> = grid
+        .regions()
+        .map(|x| (x.kind, x.area(), x.perimeter, x.sides, x.bounding_box))
+        .collect();
+    assert_eq!(
+        regions,
+        [
+            (b'A', 4, 10, 4, (Vector2d(0, 0), Vector2d(3, 0))),
+            (b'B', 4, 8, 4, (Vector2d(0, 1), Vector2d(1, 2))),
+            (b'C', 4, 10, 8, (Vector2d(2, 1), Vector2d(3, 3))),
+            (b'D', 1, 4, 4, (Vector2d(3, 1), Vector2d(3, 1))),
+            (b'E', 3, 8, 4, (Vector2d(0, 3), Vector2d(2, 3))),
+        ]
+    );
+
+    assert_eq!(part_2(TEST_INPUT_PART_1), Ok(1206));
 }
 
 #[test]
-fn check_part_1() {
-    assert_eq!(part_1(TEST_INPUT_PART_1), 1930);
+fn check_large_region() {
+    let row = "A".repeat(500);
+    let input = format!("{row}\n").repeat(500);
+    assert_eq!(part_1(&input), Ok(500 * 500 * 2000));
+    assert_eq!(part_2(&input), Ok(500 * 500 * 4));
 }
 
 #[test]
-fn check_part_2() {
-    assert_eq!(part_2(TEST_INPUT_PART_2), 236);
+fn check_render() {
+    assert_eq!(
+        render(&Grid::new("AB\nAA\n")),
+        "\x1b[48;5;160mA\x1b[48;5;34mB\x1b[0m\n\x1b[48;5;160mA\x1b[48;5;160mA\x1b[0m\n"
+    );
+
+    let colours: HashSet<String> = (0..10_000).map(background).collect();
+    assert_eq!(colours.len(), 10_000);
+
+    let mut state: u64 = 12;
+    let input: String = (0..30)
+        .map(|_| {
+            let row: String = (0..30)
+                .map(|_| {
+                    state = state
+                        .wrapping_mul(6364136223846793005)
+                        .wrapping_add(1442695040888963407);
+                    (b'A' + (state >> 33) as u8 % 4) as char
+                })
+                .collect();
+            row + "\n"
+        })
+        .collect();
+    let rendered = render(&Grid::new(&input));
+    let cells: Vec<Vec<&str>> = rendered
+        .lines()
+        .map(|line| line.split("\x1b[").skip(1).filter(|&x| x != "0m").collect())
+        .collect();
+    for y in 0..30 {
+        for x in 0..30 {
+            for (nx, ny) in [(x + 1, y), (x, y + 1)] {
+                let (Some(a), Some(b)) =
+                    (cells[y].get(x), cells.get(ny).and_then(|row| row.get(nx)))
+                else {
+                    continue;
+                };
+                // each cell is its colour code followed by its letter
+                if a.as_bytes().last() != b.as_bytes().last() {
+                    assert_ne!(a[..a.len() - 1], b[..b.len() - 1], "{x},{y}");
+                }
+            }
+        }
+    }
 }
@@ -1,5 +1,4 @@
-use regex::Regex;
-use std::sync::OnceLock;
+use aoc24::parse::{ParseError, Span};
 
 static TEST_INPUT_PART_1: &str = "Button A: X+94, Y+34
 Button B: X+22, Y+67
@@ -50,50 +49,59 @@ impl Problem {
     }
 }
 
-static REGEX: &str = r#"Button A: X\+(\d+), Y\+(\d+)
-Button B: X\+(\d+), Y\+(\d+)
-Prize: X=(\d+), Y=(\d+)"#;
-
-fn parse(input: &str) -> impl Iterator<Item = Problem> + use<'_> {
-    static RE: OnceLock<Regex> = OnceLock::new();
-    let re = RE.get_or_init(|| Regex::new(REGEX).unwrap());
-
-    re.captures_iter(input).map(|x| {
-        let get = |idx: usize| x.get(idx).unwrap().as_str().parse::<i64>().unwrap();
-        Problem {
-            a: [get(1), get(2)],
-            b: [get(3), get(4)],
-            t: [get(5), get(6)],
-        }
-    })
+fn parse(input: &str) -> Result<Vec<Problem>, ParseError> {
+    Span::new(input)
+        .split("\n\n")
+        .map(|block| {
+            let mut lines = block.lines();
+            let end = block.skip(block.text.len());
+            let mut coordinates = |x_prefix: &str, y_prefix: &str| {
+                let line = lines.next().unwrap_or(end);
+                let (x, y) = line
+                    .strip_prefix(x_prefix)?
+                    .split_once(y_prefix, y_prefix)?;
+                Ok::<_, ParseError>([x.parse("integer")?, y.parse("integer")?])
+            };
+
+            Ok(Problem {
+                a: coordinates("Button A: X+", ", Y+")?,
+                b: coordinates("Button B: X+", ", Y+")?,
+                t: coordinates("Prize: X=", ", Y=")?,
+            })
+        })
+        .collect()
 }
 
-fn part_1(input: &str) -> i64 {
-    parse(input)
+fn part_1(input: &str) -> Result<i64, ParseError> {
+    Ok(parse(input)?
+        .into_iter()
         .flat_map(|p| p.solve())
         .map(|(a, b)| a * 3 + b)
-        .sum()
+        .sum())
 }
 
-fn part_2(input: &str) -> i64 {
-    parse(input)
+fn part_2(input: &str) -> Result<i64, ParseError> {
+    Ok(parse(input)?
+        .into_iter()
         .map(|x| Problem {
             t: x.t.map(|x| x + 10000000000000),
             ..x
         })
         .flat_map(|p| p.solve())
         .map(|(a, b)| a * 3 + b)
-        .sum()
+        .sum())
 }
 
 static MY_INPUT: &str = aoc24::load_input!();
 
-fn main() {
-    println!("Part 1: {}", part_1(MY_INPUT));
-    println!("Part 1: {}", part_2(MY_INPUT));
+fn main() -> Result<(), ParseError> {
+    println!("Part 1: {}", part_1(MY_INPUT)?);
+    println!("Part 1: {}", part_2(MY_INPUT)?);
+    Ok(())
 }
 
-#[test]
-fn check_part_1() {
-    assert_eq!(part_1(TEST_INPUT_PART_1), 480);
+aoc24::examples! {
+    part_1: [
+        example: TEST_INPUT_PART_1 => 480,
+    ],
 }
@@ -5,7 +5,7 @@ use std::{
     sync::{LazyLock, OnceLock},
 };
 
-use aoc24::Vector2d;
+use aoc24::{parse, parse::ParseError, Vector2d};
 use regex::Regex;
 
 static TEST_SIZE: Vector2d = Vector2d(11, 7);
@@ -28,23 +28,25 @@ struct Robot {
     velocity: Vector2d,
 }
 
-fn parse(input: &str) -> impl Iterator<Item = Robot> + use<'_> {
+fn parse(input: &str) -> Result<Vec<Robot>, ParseError> {
     static RE: LazyLock<Regex, fn() -> Regex> =
         LazyLock::new(|| Regex::new(r"p=(-?\d*),(-?\d*) v=(-?\d*),(-?\d*)").unwrap());
 
-    RE.captures_iter(input).map(|x| {
-        let get = |idx: usize| x.get(idx).unwrap().as_str().parse::<i32>().unwrap();
-        Robot {
-            position: Vector2d(get(1), get(2)),
-            velocity: Vector2d(get(3), get(4)),
-        }
+    parse::lines(input, |line| {
+        let [px, py, vx, vy] = line
+            .captures(&RE, "p=x,y v=x,y")?
+            .map(|x| x.parse::<i32>("integer"));
+        Ok(Robot {
+            position: Vector2d(px?, py?),
+            velocity: Vector2d(vx?, vy?),
+        })
     })
 }
 
-fn part_1(input: &str, bounds: Vector2d) -> i64 {
+fn part_1(input: &str, bounds: Vector2d) -> Result<i64, ParseError> {
     let mut quadrant_counts = [0; 4];
 
-    for mut robot in parse(input) {
+    for mut robot in parse(input)? {
         robot.position += Vector2d(robot.velocity.0 * 100, robot.velocity.1 * 100);
         robot.position.0 = robot.position.0.rem_euclid(bounds.0);
         robot.position.1 = robot.position.1.rem_euclid(bounds.1);
@@ -58,7 +60,7 @@ fn part_1(input: &str, bounds: Vector2d) -> i64 {
         quadrant_counts[usize::try_from(quadrant_idx).unwrap()] += 1;
     }
 
-    quadrant_counts.into_iter().product()
+    Ok(quadrant_counts.into_iter().product())
 }
 
 fn find_connected(robots: &HashSet<Vector2d>, check: Vector2d, processed: &mut HashSet<Vector2d>) {
@@ -83,8 +85,8 @@ fn find_connected(robots: &HashSet<Vector2d>, check: Vector2d, processed: &mut H
     });
 }
 
-fn part_2(input: &str, bounds: Vector2d) -> i64 {
-    let mut robots: Vec<_> = parse(input).collect();
+fn part_2(input: &str, bounds: Vector2d) -> Result<i64, ParseError> {
+    let mut robots = parse(input)?;
 
     for iteration in 1.. {
         let mut frame = vec![b'.'; ((bounds.0 + 1) * bounds.1) as usize];
@@ -113,7 +115,7 @@ fn part_2(input: &str, bounds: Vector2d) -> i64 {
             println!("{}", iteration);
             println!("{}", variance);
             println!("{}", str::from_utf8(&frame).unwrap());
-            return iteration;
+            return Ok(iteration);
         }
     }
 
@@ -123,12 +125,14 @@ fn part_2(input: &str, bounds: Vector2d) -> i64 {
 static MY_INPUT_BOUNDS: Vector2d = Vector2d(101, 103);
 static MY_INPUT: &str = aoc24::load_input!();
 
-fn main() {
-    println!("Part 1: {}", part_1(MY_INPUT, MY_INPUT_BOUNDS));
-    println!("Part 2: {}", part_2(MY_INPUT, MY_INPUT_BOUNDS));
+fn main() -> Result<(), ParseError> {
+    println!("Part 1: {}", part_1(MY_INPUT, MY_INPUT_BOUNDS)?);
+    println!("Part 2: {}", part_2(MY_INPUT, MY_INPUT_BOUNDS)?);
+    Ok(())
 }
 
-#[test]
-fn check_part_1() {
-    assert_eq!(part_1(TEST_INPUT_PART_1, TEST_SIZE), 12);
+aoc24::examples! {
+    part_1: [
+        example: TEST_INPUT_PART_1, TEST_SIZE => 12,
+    ],
 }
@@ -1,25 +1,31 @@
 use std::collections::HashSet;
 
-use aoc24::{Grid, Vector2d};
-
-fn parse(input: &str) -> (Grid, Vec<Vector2d>) {
-    let (grid, directions) = input.split_once("\n\n").unwrap();
-
-    (
-        Grid::new(grid),
-        directions
-            .lines()
-            .flat_map(|x| {
-                x.chars().map(|x| match x {
-                    '^' => Vector2d(0, -1),
-                    '>' => Vector2d(1, 0),
-                    '<' => Vector2d(-1, 0),
-                    'v' => Vector2d(0, 1),
-                    other => panic!("BAD DIRECTION {}", other),
-                })
+use aoc24::{
+    parse::{ParseError, Span},
+    Grid, Vector2d,
+};
+
+// grid, robot, directions
+fn parse(input: &str) -> Result<(Grid, Vector2d, Vec<Vector2d>), ParseError> {
+    let (grid_span, directions) = Span::new(input).split_once("\n\n", "blank line")?;
+
+    let grid = Grid::parse(grid_span)?;
+    let robot = grid.find(b'@').ok_or_else(|| grid_span.error("@"))?;
+
+    let directions = directions
+        .lines()
+        .flat_map(|line| {
+            line.text.char_indices().map(move |(i, x)| match x {
+                '^' => Ok(Vector2d(0, -1)),
+                '>' => Ok(Vector2d(1, 0)),
+                '<' => Ok(Vector2d(-1, 0)),
+                'v' => Ok(Vector2d(0, 1)),
+                _ => Err(line.skip(i).error("^, >, < or v")),
             })
-            .collect(),
-    )
+        })
+        .collect::<Result<_, _>>()?;
+
+    Ok((grid, robot, directions))
 }
 
 fn push(things: &mut [Vector2d], to_push: usize, direction: Vector2d, grid: &Grid) -> bool {
@@ -38,10 +44,8 @@ fn push(things: &mut [Vector2d], to_push: usize, direction: Vector2d, grid: &Gri
     can_push
 }
 
-fn part_1(input: &str) -> i64 {
-    let (grid, directions) = parse(input);
-
-    let robot = grid.iter().find(|(_, c)| *c == b'@').unwrap().0;
+fn part_1(input: &str) -> Result<i64, ParseError> {
+    let (grid, robot, directions) = parse(input)?;
 
     let mut things = vec![robot];
     things.extend(grid.iter().filter(|(_, c)| *c == b'O').map(|(x, _)| x));
@@ -50,11 +54,11 @@ fn part_1(input: &str) -> i64 {
         push(&mut things, 0, direction, &grid);
     }
 
-    things[1..]
+    Ok(things[1..]
         .iter()
         .copied()
         .map(|x| x.0 as i64 + x.1 as i64 * 100)
-        .sum()
+        .sum())
 }
 
 fn is_hitting_box(box_pos: Vector2d, pos: Vector2d) -> bool {
@@ -147,10 +151,9 @@ fn push_big_boxes(
     can_push
 }
 
-fn part_2(input: &str) -> i64 {
-    let (grid, directions) = parse(input);
+fn part_2(input: &str) -> Result<i64, ParseError> {
+    let (grid, robot, directions) = parse(input)?;
 
-    let robot = grid.iter().find(|(_, c)| *c == b'@').unwrap().0;
     let robot = Vector2d(robot.0 * 2, robot.1);
 
     let mut things = vec![robot];
@@ -173,16 +176,22 @@ fn part_2(input: &str) -> i64 {
         );
     }
 
-    things[1..]
+    Ok(things[1..]
         .iter()
         .copied()
         .map(|x| x.0 as i64 + x.1 as i64 * 100)
-        .sum()
+        .sum())
 }
 
 static MY_INPUT: &str = aoc24::load_input!();
 
-fn main() {
-    println!("Part 1: {}", part_1(MY_INPUT));
-    println!("Part 2: {}", part_2(MY_INPUT));
+fn main() -> Result<(), ParseError> {
+    println!("Part 1: {}", part_1(MY_INPUT)?);
+    println!("Part 2: {}", part_2(MY_INPUT)?);
+    Ok(())
+}
+
+aoc24::example_tests! {
+    part_1: part_1,
+    part_2: part_2,
 }
@@ -1,7 +1,10 @@
 use std::collections::{HashMap, HashSet};
 
-use aoc24::{Grid, Vector2d};
-use petgraph::{graph::NodeIndex, Graph};
+use aoc24::{
+    parse::{ParseError, Span},
+    Grid, Vector2d,
+};
+use petgraph::{graph::NodeIndex, visit::EdgeRef, Direction, Graph};
 
 static MY_INPUT: &str = aoc24::load_input!();
 
@@ -22,132 +25,212 @@ static TEST_INPUT_PART_1: &str = "###############
 ###############
 ";
 
-fn part_1(input: &str) -> i64 {
-    let grid = Grid::new(input);
+static TEST_INPUT_PART_2: &str = "#################
+#...#...#...#..E#
+#.#.#.#.#.#.#.#.#
+#.#.#.#...#...#.#
+#.#.#.#.###.#.#.#
+#...#.#.#.....#.#
+#.#.#.#.#.#####.#
+#.#...#.#.#.....#
+#.#.#####.#.###.#
+#.#.#.......#...#
+#.#.###.#####.###
+#.#.#...#.....#.#
+#.#.#.#####.###.#
+#.#.#.........#.#
+#.#.#.#########.#
+#S#.............#
+#################
+";
 
-    let start = grid.iter().find(|(_, c)| *c == b'S').unwrap().0;
-    let end = grid.iter().find(|(_, c)| *c == b'E').unwrap().0;
+struct Maze {
+    grid: Grid,
+    /// One node per open tile and direction the reindeer can face on it, in
+    /// the same order as [`Vector2d::DIRECTIONS`].
+    graph: Graph<Vector2d, i64>,
+    lookup: HashMap<Vector2d, [NodeIndex; 4]>,
+    start: Vector2d,
+    end: Vector2d,
+}
 
-    let mut pg = petgraph::Graph::new();
-    let mut lookup = HashMap::new();
+struct Solution {
+    cost: i64,
+    /// Every tile that is on at least one of the cheapest paths.
+    tiles: HashSet<Vector2d>,
+}
 
-    for (node, c) in grid.iter() {
-        if c == b'#' {
-            continue;
-        }
-        lookup.insert(node, [0, 0, 0, 0].map(|_| pg.add_node(0)));
-    }
+impl Maze {
+    fn new(input: &str, move_cost: i64, turn_cost: i64) -> Result<Self, ParseError> {
+        let span = Span::new(input);
+        let grid = Grid::parse(span)?;
 
-    for (&node, a) in lookup.iter() {
-        for (idx, neighbor) in node.neighbours().enumerate() {
-            let Some(corresponding) = lookup.get(&neighbor) else {
-                continue;
-            };
+        let start = grid.find(b'S').ok_or_else(|| span.error("S"))?;
+        let end = grid.find(b'E').ok_or_else(|| span.error("E"))?;
 
-            let b = corresponding;
+        let mut graph = Graph::new();
+        let mut lookup = HashMap::new();
 
-            pg.add_edge(a[idx], b[idx], 1);
+        for (node, c) in grid.iter() {
+            if c == b'#' {
+                continue;
+            }
+            lookup.insert(node, [0, 0, 0, 0].map(|_| graph.add_node(node)));
         }
 
-        for idx in 0..4 {
-            pg.add_edge(a[idx], a[(idx + 1) % 4], 1000);
-            pg.add_edge(a[idx], a[(idx as isize - 1).rem_euclid(4) as usize], 1000);
-        }
-    }
+        for (&node, a) in lookup.iter() {
+            for (idx, neighbor) in node.neighbours().enumerate() {
+                let Some(b) = lookup.get(&neighbor) else {
+                    continue;
+                };
 
-    let solved = petgraph::algo::dijkstra(&pg, lookup[&start][0], None, |e| *e.weight() as i64);
-
-    lookup[&end].iter().map(|x| solved[x]).min().unwrap()
-}
-
-fn part_2(input: &str) -> i64 {
-    let grid = Grid::new(input);
-
-    let start = grid.iter().find(|(_, c)| *c == b'S').unwrap().0;
-    let end = grid.iter().find(|(_, c)| *c == b'E').unwrap().0;
-
-    let mut pg = petgraph::Graph::new();
-    let mut lookup = HashMap::new();
+                graph.add_edge(a[idx], b[idx], move_cost);
+            }
 
-    for (node, c) in grid.iter() {
-        if c == b'#' {
-            continue;
+            for idx in 0..4 {
+                graph.add_edge(a[idx], a[(idx + 1) % 4], turn_cost);
+                graph.add_edge(a[idx], a[(idx + 3) % 4], turn_cost);
+            }
         }
-        lookup.insert(node, [0, 0, 0, 0].map(|_| pg.add_node(node)));
-    }
-
-    for (&node, a) in lookup.iter() {
-        for (idx, neighbor) in node.neighbours().enumerate() {
-            let Some(corresponding) = lookup.get(&neighbor) else {
-                continue;
-            };
 
-            let b = corresponding;
+        Ok(Self {
+            grid,
+            graph,
+            lookup,
+            start,
+            end,
+        })
+    }
 
-            pg.add_edge(a[idx], b[idx], 1);
+    /// Finds the lowest score, then walks back from the end along every edge
+    /// that is on a shortest path to find the tiles of all the best paths.
+    fn solve(&self) -> Solution {
+        let solved =
+            petgraph::algo::dijkstra(&self.graph, self.lookup[&self.start][0], None, |e| {
+                *e.weight()
+            });
+
+        let cost = self.lookup[&self.end]
+            .iter()
+            .filter_map(|x| solved.get(x))
+            .copied()
+            .min()
+            .unwrap();
+
+        let mut stack: Vec<_> = self.lookup[&self.end]
+            .iter()
+            .copied()
+            .filter(|x| solved.get(x) == Some(&cost))
+            .collect();
+        let mut seen: HashSet<NodeIndex> = stack.iter().copied().collect();
+
+        while let Some(idx) = stack.pop() {
+            for edge in self.graph.edges_directed(idx, Direction::Incoming) {
+                let previous = edge.source();
+                if solved.get(&previous).map(|x| x + edge.weight()) == Some(solved[&idx])
+                    && seen.insert(previous)
+                {
+                    stack.push(previous);
+                }
+            }
         }
 
-        for idx in 0..4 {
-            pg.add_edge(a[idx], a[(idx + 1) % 4], 1000);
-            pg.add_edge(a[idx], a[(idx as isize - 1).rem_euclid(4) as usize], 1000);
+        Solution {
+            cost,
+            tiles: seen.into_iter().map(|idx| self.graph[idx]).collect(),
         }
     }
 
-    let solved = petgraph::algo::dijkstra(&pg, lookup[&start][0], None, |e| *e.weight() as i64);
-
-    let end_weight = lookup[&end].iter().map(|x| solved[x]).min().unwrap();
-    let ends = lookup[&end].iter().filter(|&x| solved[x] == end_weight);
-    let mut explored = HashSet::new();
-
-    fn explore(
-        graph: &Graph<Vector2d, i32>,
-        solved: &HashMap<NodeIndex<u32>, i64>,
-        lookup: &HashMap<Vector2d, [NodeIndex; 4]>,
-        explored: &mut HashSet<Vector2d>,
-        idx: NodeIndex<u32>,
-    ) {
-        let pos = graph[idx];
-        explored.insert(pos);
-
-        let weight = solved[&idx];
-
-        for n in pos.neighbours().chain([pos].into_iter()) {
-            let Some(l) = lookup.get(&n) else {
-                continue;
-            };
-
-            for n in l
-                .iter()
-                .copied()
-                .filter(|&x| graph.neighbors(x).any(|b| b == idx))
-            {
-                let edge = graph.edges_connecting(n, idx).next().unwrap();
-                let w = solved[&n];
-                if weight == w + (*edge.weight() as i64) {
-                    explore(graph, solved, lookup, explored, n);
+    /// Draws the maze with `O` on every tile of the best paths.
+    fn render(&self, solution: &Solution) -> String {
+        let mut output = String::new();
+        for y in 0..self.grid.height as i32 {
+            for x in 0..self.grid.width as i32 {
+                let position = Vector2d(x, y);
+                if solution.tiles.contains(&position) {
+                    output.push('O');
+                } else {
+                    output.push(self.grid.get(position).unwrap() as char);
                 }
             }
+            output.push('\n');
         }
+        output
     }
+}
 
-    for &end in ends {
-        explore(&pg, &solved, &lookup, &mut explored, end);
+// both parts come from the same search, so they share its solution
+fn part_1(solution: &Solution) -> i64 {
+    solution.cost
+}
+
+fn part_2(solution: &Solution) -> i64 {
+    solution.tiles.len() as i64
+}
+
+fn main() -> Result<(), ParseError> {
+    let maze = Maze::new(MY_INPUT, 1, 1000)?;
+    let solution = maze.solve();
+
+    if std::env::args().any(|x| x == "--render") {
+        print!("{}", maze.render(&solution));
     }
 
-    explored.len() as i64
+    println!("Part 1: {}", part_1(&solution));
+    println!("Part 2: {}", part_2(&solution));
+    Ok(())
 }
 
-fn main() {
-    println!("Part 1: {}", part_1(MY_INPUT));
-    println!("Part 2: {}", part_2(MY_INPUT));
+aoc24::examples! {
+    part_1: [
+        first_example: &Maze::new(TEST_INPUT_PART_1, 1, 1000).unwrap().solve() => 7036,
+        second_example: &Maze::new(TEST_INPUT_PART_2, 1, 1000).unwrap().solve() => 11048,
+    ],
+    part_2: [
+        first_example: &Maze::new(TEST_INPUT_PART_1, 1, 1000).unwrap().solve() => 45,
+        second_example: &Maze::new(TEST_INPUT_PART_2, 1, 1000).unwrap().solve() => 64,
+    ],
 }
 
 #[test]
-fn check_part_1() {
-    assert_eq!(part_1(TEST_INPUT_PART_1), 7036);
+fn check_costs() {
+    // with free turns it is just the shortest path through the maze
+    let maze = Maze::new(TEST_INPUT_PART_1, 1, 0).unwrap();
+    assert_eq!(maze.solve().cost, 28);
+
+    let maze = Maze::new(TEST_INPUT_PART_1, 2, 1000).unwrap();
+    assert_eq!(maze.solve().cost, 7036 + 36);
+    assert_eq!(maze.solve().tiles.len(), 45);
+}
+
+#[test]
+fn check_render() {
+    let maze = Maze::new(TEST_INPUT_PART_1, 1, 1000).unwrap();
+    assert_eq!(
+        maze.render(&maze.solve()),
+        "###############
+#.......#....O#
+#.#.###.#.###O#
+#.....#.#...#O#
+#.###.#####.#O#
+#.#.#.......#O#
+#.#.#####.###O#
+#..OOOOOOOOO#O#
+###O#O#####O#O#
+#OOO#O....#O#O#
+#O#O#O###.#O#O#
+#OOOOO#...#O#O#
+#O###.#.#.#O#O#
+#O..#.....#OOO#
+###############
+"
+    );
 }
 
 #[test]
-fn check_part_2() {
-    assert_eq!(part_2(TEST_INPUT_PART_1), 45);
+fn check_parse_errors() {
+    let error = Maze::new("####\n#SE#\n###\n", 1, 1000).err().unwrap();
+    assert_eq!(error.to_string(), "line 3: expected `a row 4 wide`");
+    let error = Maze::new("####\n#S.#\n####\n", 1, 1000).err().unwrap();
+    assert_eq!(error.to_string(), "line 1: expected `E`");
 }
@@ -1,3 +1,4 @@
+use aoc24::parse::{ParseError, Span};
 use itertools::Itertools;
 
 #[derive(Debug, Clone)]
@@ -149,49 +150,48 @@ impl Machine {
     }
 }
 
-fn parse(input: &str) -> (Machine, Program) {
-    let mut lines = input.lines();
-    let mut get_register = || {
+fn parse(input: &str) -> Result<(Machine, Program), ParseError> {
+    let (registers, program) = Span::new(input).split_once("\n\n", "blank line")?;
+
+    let mut lines = registers.lines();
+    let end = registers.skip(registers.text.len());
+    let mut get_register = |name: &str| {
         lines
             .next()
-            .unwrap()
-            .rsplit_once(' ')
-            .unwrap()
-            .1
-            .parse::<i64>()
-            .unwrap()
+            .unwrap_or(end)
+            .strip_prefix(&format!("Register {name}: "))?
+            .parse::<i64>("integer")
     };
 
-    let registers = [get_register(), get_register(), get_register()];
+    let registers = [get_register("A")?, get_register("B")?, get_register("C")?];
 
-    lines.next().unwrap();
-    let program = lines
-        .next()
-        .unwrap()
-        .split_once(' ')
-        .unwrap()
-        .1
-        .split(',')
-        .map(|x| x.parse::<u8>().unwrap())
-        .collect();
+    let program = program
+        .trim()
+        .strip_prefix("Program: ")?
+        .split(",")
+        .map(|x| match x.parse::<u8>("3-bit number")? {
+            value @ 0..8 => Ok(value),
+            _ => Err(x.error("3-bit number")),
+        })
+        .collect::<Result<_, _>>()?;
 
-    (
+    Ok((
         Machine {
             registers,
             instruction_pointer: 0,
         },
         Program(program),
-    )
+    ))
 }
 
-fn part_1(input: &str) -> String {
-    let (mut machine, program) = parse(input);
+fn part_1(input: &str) -> Result<String, ParseError> {
+    let (mut machine, program) = parse(input)?;
     let mut output = Vec::new();
     while !machine.halted(&program) {
         machine.execute_instruction(&program, &mut output);
     }
 
-    output.into_iter().map(|x| x.to_string()).join(",")
+    Ok(output.into_iter().map(|x| x.to_string()).join(","))
 }
 
 // specific for my program
@@ -220,14 +220,20 @@ fn reverse(a: u64, expected_output: &[u8]) -> Option<u64> {
     None
 }
 
-fn part_2(input: &str) -> i64 {
-    let (_, program) = parse(input);
-    reverse(0, &program.0).unwrap() as i64
+fn part_2(input: &str) -> Result<i64, ParseError> {
+    let (_, program) = parse(input)?;
+    Ok(reverse(0, &program.0).unwrap() as i64)
 }
 
 static MY_INPUT: &str = aoc24::load_input!();
 
-fn main() {
-    println!("Part 1: {}", part_1(MY_INPUT));
-    println!("Part 2: {}", part_2(MY_INPUT));
+fn main() -> Result<(), ParseError> {
+    println!("Part 1: {}", part_1(MY_INPUT)?);
+    println!("Part 2: {}", part_2(MY_INPUT)?);
+    Ok(())
+}
+
+aoc24::example_tests! {
+    part_1: part_1,
+    part_2: part_2,
 }
@@ -1,22 +1,22 @@
 use std::collections::{HashMap, HashSet};
 
-use aoc24::Vector2d;
+use aoc24::{parse, parse::ParseError, Vector2d};
 use petgraph::{algo::dijkstra, Graph};
 
-fn part_1(input: &str) -> i64 {
-    let corrupted: HashSet<_> = input
-        .lines()
-        .map(|x| {
-            let (a, b) = x.split_once(',').unwrap();
-            Vector2d(a.parse::<i32>().unwrap(), b.parse::<i32>().unwrap())
-        })
-        .take(1024)
-        .collect();
+fn parse(input: &str) -> Result<Vec<Vector2d>, ParseError> {
+    parse::lines(input, |line| {
+        let (a, b) = line.split_once(",", "x,y")?;
+        Ok(Vector2d(a.parse("integer")?, b.parse("integer")?))
+    })
+}
+
+fn part_1(input: &str, size: i32, bytes: usize) -> Result<i64, ParseError> {
+    let corrupted: HashSet<_> = parse(input)?.into_iter().take(bytes).collect();
 
     let mut graph = Graph::new_undirected();
     let mut lookup = HashMap::new();
 
-    for coord in (0..=70).flat_map(|y| (0..=70).map(move |x| Vector2d(x, y))) {
+    for coord in (0..=size).flat_map(|y| (0..=size).map(move |x| Vector2d(x, y))) {
         if corrupted.contains(&coord) {
             continue;
         }
@@ -34,26 +34,23 @@ fn part_1(input: &str) -> i64 {
     let r = dijkstra(
         &graph,
         lookup[&Vector2d(0, 0)],
-        Some(lookup[&Vector2d(70, 70)]),
+        Some(lookup[&Vector2d(size, size)]),
         |_| 1i64,
     );
 
-    let end = lookup[&Vector2d(70, 70)];
-    r[&end]
+    let end = lookup[&Vector2d(size, size)];
+    Ok(r[&end])
 }
 
-fn part_2(input: &str) -> Vector2d {
+fn part_2(input: &str, size: i32) -> Result<Vector2d, ParseError> {
     let mut corrupted_coordinates = HashSet::new();
 
-    for corrupted_coordinate in input.lines().map(|x| {
-        let (a, b) = x.split_once(',').unwrap();
-        Vector2d(a.parse::<i32>().unwrap(), b.parse::<i32>().unwrap())
-    }) {
+    for corrupted_coordinate in parse(input)? {
         corrupted_coordinates.insert(corrupted_coordinate);
         let mut graph = Graph::new_undirected();
         let mut lookup = HashMap::new();
 
-        for coord in (0..=70).flat_map(|y| (0..=70).map(move |x| Vector2d(x, y))) {
+        for coord in (0..=size).flat_map(|y| (0..=size).map(move |x| Vector2d(x, y))) {
             if corrupted_coordinates.contains(&coord) {
                 continue;
             }
@@ -71,23 +68,34 @@ fn part_2(input: &str) -> Vector2d {
         let r = dijkstra(
             &graph,
             lookup[&Vector2d(0, 0)],
-            Some(lookup[&Vector2d(70, 70)]),
+            Some(lookup[&Vector2d(size, size)]),
             |_| 1i64,
         );
 
-        let end = lookup[&Vector2d(70, 70)];
+        let end = lookup[&Vector2d(size, size)];
         if !r.contains_key(&end) {
-            return corrupted_coordinate;
+            return Ok(corrupted_coordinate);
         }
     }
 
     panic!()
 }
 
+static MY_INPUT_SIZE: i32 = 70;
+static MY_INPUT_BYTES: usize = 1024;
 static MY_INPUT: &str = aoc24::load_input!();
 
-fn main() {
-    println!("Part 1: {}", part_1(MY_INPUT));
-    let p2 = part_2(MY_INPUT);
+fn main() -> Result<(), ParseError> {
+    println!(
+        "Part 1: {}",
+        part_1(MY_INPUT, MY_INPUT_SIZE, MY_INPUT_BYTES)?
+    );
+    let p2 = part_2(MY_INPUT, MY_INPUT_SIZE)?;
     println!("Part 2: {},{}", p2.0, p2.1);
+    Ok(())
+}
+
+aoc24::example_tests! {
+    part_1: |x| part_1(x, 6, 12),
+    part_2: |x| part_2(x, 6).map(|p2| format!("{},{}", p2.0, p2.1)),
 }
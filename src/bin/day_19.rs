@@ -1,69 +1,155 @@
-use std::collections::{HashMap, HashSet};
+use aoc24::parse::{ParseError, Span};
+use num_bigint::BigUint;
+
+static TEST_INPUT: &str = include_str!("../../fixtures/day_19_1.txt");
 
 // patterns, designs
-fn parse(input: &str) -> (Vec<&str>, Vec<&str>) {
-    let (patterns, designs) = input.split_once("\n\n").unwrap();
-    let patterns = patterns.split(',').map(|x| x.trim()).collect();
-    let designs = designs.lines().map(|x| x.trim()).collect();
-    (patterns, designs)
+fn parse(input: &str) -> Result<(Vec<&str>, Vec<&str>), ParseError> {
+    // the trie only has room for lowercase letters
+    fn letters(x: Span<'_>) -> Result<&str, ParseError> {
+        let x = x.trim();
+        match x.text.bytes().position(|x| !x.is_ascii_lowercase()) {
+            Some(i) => Err(x.skip(i).error("lowercase letter")),
+            None => Ok(x.text),
+        }
+    }
+
+    let (patterns, designs) = Span::new(input).split_once("\n\n", "blank line")?;
+    let patterns = patterns.split(",").map(letters).collect::<Result<_, _>>()?;
+    let designs = designs.lines().map(letters).collect::<Result<_, _>>()?;
+    Ok((patterns, designs))
 }
 
-fn is_possible<'a>(design: &'a str, patterns: &[&str], cache: &mut HashMap<&'a str, bool>) -> bool {
-    if let Some(&cached) = cache.get(design) {
-        return cached;
-    }
-    if design.is_empty() {
-        return true;
-    }
-    let result = patterns
-        .iter()
-        .filter_map(|&x| design.strip_prefix(x))
-        .any(|x| is_possible(x, patterns, cache));
-    cache.insert(design, result);
-    result
+#[derive(Default, Clone)]
+struct TrieNode {
+    /// Index of the child node for each letter, with 0 meaning no child since
+    /// the root is never a child.
+    children: [usize; 26],
+    is_pattern: bool,
 }
 
-fn part_1(input: &str) -> i64 {
-    let (patterns, designs) = parse(input);
-    let mut cache = HashMap::new();
-    designs
-        .iter()
-        .filter(|&&x| is_possible(x, &patterns, &mut cache))
-        .count() as i64
+/// All the towel patterns, so the patterns that start a design at some
+/// position can be found in a single walk down the trie.
+struct Trie {
+    nodes: Vec<TrieNode>,
 }
 
-fn count_possible<'a>(
-    design: &'a str,
-    patterns: &[&str],
-    cache: &mut HashMap<&'a str, i64>,
-) -> i64 {
-    if let Some(&cached) = cache.get(design) {
-        return cached;
+impl Trie {
+    fn new(patterns: &[&str]) -> Self {
+        let mut nodes = vec![TrieNode::default()];
+        for pattern in patterns {
+            let mut node = 0;
+            for letter in pattern.bytes() {
+                let child = (letter - b'a') as usize;
+                if nodes[node].children[child] == 0 {
+                    nodes[node].children[child] = nodes.len();
+                    nodes.push(TrieNode::default());
+                }
+                node = nodes[node].children[child];
+            }
+            nodes[node].is_pattern = true;
+        }
+
+        Self { nodes }
     }
-    if design.is_empty() {
-        return 1;
+
+    /// The end positions of every pattern that matches `design` from `start`.
+    fn matches<'a>(&'a self, design: &'a [u8], start: usize) -> impl Iterator<Item = usize> + 'a {
+        design[start..]
+            .iter()
+            .scan(0, |node, &letter| {
+                *node = self.nodes[*node].children[(letter - b'a') as usize];
+                (*node != 0).then_some(*node)
+            })
+            .enumerate()
+            .filter(|&(_, node)| self.nodes[node].is_pattern)
+            .map(move |(len, _)| start + len + 1)
+    }
+
+    /// For every position in the design, the number of ways to make the rest
+    /// of the design from there.
+    fn arrangements_from(&self, design: &str) -> Vec<BigUint> {
+        let design = design.as_bytes();
+        let mut ways = vec![BigUint::ZERO; design.len() + 1];
+        ways[design.len()] = 1_u32.into();
+
+        for start in (0..design.len()).rev() {
+            ways[start] = self.matches(design, start).map(|end| &ways[end]).sum();
+        }
+
+        ways
+    }
+
+    fn count_arrangements(&self, design: &str) -> BigUint {
+        self.arrangements_from(design).swap_remove(0)
+    }
+
+    /// One way of making the design out of patterns, if there is any.
+    fn arrangement<'a>(&self, design: &'a str) -> Option<Vec<&'a str>> {
+        let ways = self.arrangements_from(design);
+
+        let mut arrangement = Vec::new();
+        let mut start = 0;
+        while start != design.len() {
+            let end = self
+                .matches(design.as_bytes(), start)
+                .find(|&end| ways[end] != BigUint::ZERO)?;
+            arrangement.push(&design[start..end]);
+            start = end;
+        }
+
+        Some(arrangement)
     }
-    let result = patterns
-        .iter()
-        .filter_map(|&x| design.strip_prefix(x))
-        .map(|x| count_possible(x, patterns, cache))
-        .sum();
-    cache.insert(design, result);
-    result
 }
 
-fn part_2(input: &str) -> i64 {
-    let (patterns, designs) = parse(input);
-    let mut cache = HashMap::new();
-    designs
+fn part_1(input: &str) -> Result<i64, ParseError> {
+    let (patterns, designs) = parse(input)?;
+    let trie = Trie::new(&patterns);
+    Ok(designs
         .iter()
-        .map(|&x| count_possible(x, &patterns, &mut cache))
-        .sum()
+        .filter(|&&x| trie.count_arrangements(x) != BigUint::ZERO)
+        .count() as i64)
+}
+
+fn part_2(input: &str) -> Result<BigUint, ParseError> {
+    let (patterns, designs) = parse(input)?;
+    let trie = Trie::new(&patterns);
+    Ok(designs.iter().map(|&x| trie.count_arrangements(x)).sum())
 }
 
 static MY_INPUT: &str = aoc24::load_input!();
 
-fn main() {
-    println!("Part 1: {}", part_1(MY_INPUT));
-    println!("Part 2: {}", part_2(MY_INPUT));
+fn main() -> Result<(), ParseError> {
+    if std::env::args().any(|x| x == "--arrangements") {
+        let (patterns, designs) = parse(MY_INPUT)?;
+        let trie = Trie::new(&patterns);
+        for design in designs {
+            match trie.arrangement(design) {
+                Some(arrangement) => println!("{}: {}", design, arrangement.join(" ")),
+                None => println!("{}: impossible", design),
+            }
+        }
+    }
+
+    println!("Part 1: {}", part_1(MY_INPUT)?);
+    println!("Part 2: {}", part_2(MY_INPUT)?);
+    Ok(())
+}
+
+#[test]
+fn check_arrangement() {
+    let (patterns, _) = parse(TEST_INPUT).unwrap();
+    let trie = Trie::new(&patterns);
+
+    assert_eq!(trie.arrangement("brwrr"), Some(vec!["b", "r", "wr", "r"]));
+    assert_eq!(trie.arrangement("ubwu"), None);
+    assert_eq!(trie.count_arrangements("gbbr"), 4_u32.into());
+
+    let huge = trie.count_arrangements(&"br".repeat(200));
+    assert!(huge > u128::MAX.into());
+}
+
+aoc24::example_tests! {
+    part_1: part_1,
+    part_2: part_2,
 }
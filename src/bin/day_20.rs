@@ -1,59 +1,180 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, VecDeque};
 
-use aoc24::{Grid, Vector2d};
-use petgraph::{algo::dijkstra, Graph};
+use aoc24::{
+    parse::{ParseError, Span},
+    Grid, Vector2d,
+};
 
-fn part_1(input: &str, cheat_time: i64) -> i64 {
-    let grid = Grid::new(input);
+static TEST_INPUT: &str = include_str!("../../fixtures/day_20_1.txt");
 
-    let mut lookup = HashMap::new();
-    let mut graph = Graph::new();
-    let start = grid.iter().find(|(_, x)| *x == b'S').unwrap().0;
+// racetrack, start
+fn parse(input: &str) -> Result<(Grid, Vector2d), ParseError> {
+    let span = Span::new(input);
+    let grid = Grid::parse(span)?;
+    let start = grid.find(b'S').ok_or_else(|| span.error("S"))?;
+    Ok((grid, start))
+}
 
-    for (coord, l) in grid.iter() {
-        if l == b'#' {
-            continue;
-        }
-        lookup.insert(coord, graph.add_node(coord));
-    }
+/// The distance from the start to every track cell, or `None` for walls.
+fn distances(grid: &Grid, start: Vector2d) -> Vec<Option<i64>> {
+    let index = |v: Vector2d| v.0 as usize + v.1 as usize * grid.width;
 
-    for (&coord, &idx) in lookup.iter() {
-        for n in coord.neighbours() {
-            if let Some(&n) = lookup.get(&n) {
-                graph.add_edge(idx, n, ());
+    let mut distances = vec![None; grid.width * grid.height];
+    distances[index(start)] = Some(0);
+
+    let mut queue = VecDeque::from([(start, 0)]);
+    while let Some((position, distance)) = queue.pop_front() {
+        for neighbour in position.neighbours() {
+            if matches!(grid.get(neighbour), None | Some(b'#')) {
+                continue;
+            }
+            if distances[index(neighbour)].is_none() {
+                distances[index(neighbour)] = Some(distance + 1);
+                queue.push_back((neighbour, distance + 1));
             }
         }
     }
 
+    distances
+}
+
+/// How many cheats of up to `cheat_time` picoseconds save each amount of
+/// time, for every saving above zero.
+fn cheat_savings(input: &str, cheat_time: i64) -> Result<BTreeMap<i64, i64>, ParseError> {
+    let (grid, start) = parse(input)?;
+    let distances = distances(&grid, start);
+    let distance = |v: Vector2d| {
+        grid.get(v)
+            .and_then(|_| distances[v.0 as usize + v.1 as usize * grid.width])
+    };
+
     let ct = cheat_time as i32;
-    let cheat_pattern: Vec<_> = (-ct - 1..=ct + 1)
-        .flat_map(|y| (-ct - 1..=ct + 1).map(move |x| (x, y)))
-        .map(|(x, y)| Vector2d(x, y))
+    let cheat_pattern: Vec<_> = (-ct..=ct)
+        .flat_map(|y| (-ct..=ct).map(move |x| Vector2d(x, y)))
         .filter(|&x| x.manhattan() <= ct)
         .collect();
 
-    let result = dijkstra(&graph, lookup[&start], None, |_| 1i64);
-    let result = &result;
-    lookup
-        .iter()
-        .flat_map(|(&coord, &idx)| {
-            let this_cost = result[&idx];
-
-            cheat_pattern
-                .iter()
-                .copied()
-                .map(move |cheat| (coord + cheat, cheat.manhattan()))
-                .filter_map(|(x, time)| lookup.get(&x).map(|x| (x, time)))
-                .map(move |(x, time)| result[x] - this_cost - time as i64)
-        })
-        .filter(|&x| x > 0)
-        .filter(|&x| x >= 100)
-        .count() as i64
+    let mut savings = BTreeMap::new();
+    for (coord, _) in grid.iter() {
+        let Some(this_cost) = distance(coord) else {
+            continue;
+        };
+
+        for &cheat in &cheat_pattern {
+            let Some(cost) = distance(coord + cheat) else {
+                continue;
+            };
+
+            let saving = cost - this_cost - cheat.manhattan() as i64;
+            if saving > 0 {
+                *savings.entry(saving).or_default() += 1;
+            }
+        }
+    }
+
+    Ok(savings)
+}
+
+fn count_cheats(input: &str, cheat_time: i64, threshold: i64) -> Result<i64, ParseError> {
+    Ok(cheat_savings(input, cheat_time)?
+        .range(threshold..)
+        .map(|(_, count)| count)
+        .sum())
+}
+
+fn part_1(input: &str, threshold: i64) -> Result<i64, ParseError> {
+    count_cheats(input, 2, threshold)
+}
+
+fn part_2(input: &str, threshold: i64) -> Result<i64, ParseError> {
+    count_cheats(input, 20, threshold)
 }
 
 static MY_INPUT: &str = aoc24::load_input!();
 
-fn main() {
-    println!("Part 1: {}", part_1(MY_INPUT, 2));
-    println!("Part 2: {}", part_1(MY_INPUT, 20));
+fn main() -> Result<(), ParseError> {
+    println!("Part 1: {}", part_1(MY_INPUT, 100)?);
+    println!("Part 2: {}", part_2(MY_INPUT, 100)?);
+    Ok(())
+}
+
+#[test]
+fn check_cheat_savings() {
+    assert_eq!(
+        cheat_savings(TEST_INPUT, 2).unwrap(),
+        BTreeMap::from([
+            (2, 14),
+            (4, 14),
+            (6, 2),
+            (8, 4),
+            (10, 2),
+            (12, 3),
+            (20, 1),
+            (36, 1),
+            (38, 1),
+            (40, 1),
+            (64, 1),
+        ])
+    );
+}
+
+#[test]
+fn check_long_cheat_savings() {
+    let savings = cheat_savings(TEST_INPUT, 20).unwrap();
+    assert_eq!(
+        savings
+            .range(50..)
+            .map(|(&a, &b)| (a, b))
+            .collect::<Vec<_>>(),
+        [
+            (50, 32),
+            (52, 31),
+            (54, 29),
+            (56, 39),
+            (58, 25),
+            (60, 23),
+            (62, 20),
+            (64, 19),
+            (66, 12),
+            (68, 14),
+            (70, 12),
+            (72, 22),
+            (74, 4),
+            (76, 3),
+        ]
+    );
+}
+
+aoc24::examples! {
+    part_1: [
+        save_64: TEST_INPUT, 64 => 1,
+    ],
+    part_2: [
+        save_76: TEST_INPUT, 76 => 3,
+    ],
+    count_cheats: [
+        two_picoseconds_save_38: TEST_INPUT, 2, 38 => 3,
+        twenty_picoseconds_save_74: TEST_INPUT, 20, 74 => 7,
+    ],
+}
+
+aoc24::example_tests! {
+    part_1: |x| part_1(x, 20),
+    part_2: |x| part_2(x, 50),
+}
+
+#[test]
+fn check_parse_errors() {
+    assert_eq!(
+        part_1("", 0).unwrap_err().to_string(),
+        "line 1: expected `a grid`"
+    );
+    assert_eq!(
+        part_1("###\n#E#\n##\n", 0).unwrap_err().to_string(),
+        "line 3: expected `a row 3 wide`"
+    );
+    assert_eq!(
+        part_1("###\n#E#\n###\n", 0).unwrap_err().to_string(),
+        "line 1: expected `S`"
+    );
 }
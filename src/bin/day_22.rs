@@ -0,0 +1,113 @@
+use aoc24::parse::{self, ParseError};
+
+static TEST_INPUT_PART_1: &str = "1
+10
+100
+2024
+";
+
+static TEST_INPUT_PART_2: &str = "1
+2
+3
+2024
+";
+
+const PRUNE_MASK: u32 = (1 << 24) - 1;
+
+/// Moves a secret number on to the next one. Every step is a mix then a prune,
+/// and since pruning keeps the bottom 24 bits any bits lost off the top of the
+/// `u32` don't matter.
+fn evolve(mut secret: u32) -> u32 {
+    secret = ((secret << 6) ^ secret) & PRUNE_MASK;
+    secret = ((secret >> 5) ^ secret) & PRUNE_MASK;
+    ((secret << 11) ^ secret) & PRUNE_MASK
+}
+
+/// Moves every secret on by one step. This is a plain loop over the slice
+/// with no branches so it gets vectorised.
+fn evolve_all(secrets: &mut [u32]) {
+    for secret in secrets {
+        *secret = evolve(*secret);
+    }
+}
+
+/// The secret numbers a buyer generates, not including the initial one.
+struct SecretNumbers(u32);
+
+impl Iterator for SecretNumbers {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0 = evolve(self.0);
+        Some(self.0)
+    }
+}
+
+fn parse(input: &str) -> Result<Vec<u32>, ParseError> {
+    parse::lines(input, |x| x.parse("integer"))
+}
+
+fn part_1(input: &str) -> Result<i64, ParseError> {
+    let mut secrets = parse(input)?;
+    for _ in 0..2000 {
+        evolve_all(&mut secrets);
+    }
+    Ok(secrets.iter().map(|&x| x as i64).sum())
+}
+
+/// Each price change is between -9 and 9, so a window of 4 of them is a 4
+/// digit base 19 number.
+const WINDOWS: usize = 19 * 19 * 19 * 19;
+
+fn part_2(input: &str) -> Result<i64, ParseError> {
+    let mut totals = vec![0_i64; WINDOWS];
+    // which buyer last sold at each window, so only the first time counts
+    let mut seen = vec![usize::MAX; WINDOWS];
+
+    for (buyer, secret) in parse(input)?.into_iter().enumerate() {
+        let mut price = (secret % 10) as i64;
+        let mut window = 0;
+
+        for (step, next) in SecretNumbers(secret).take(2000).enumerate() {
+            let next_price = (next % 10) as i64;
+            window = (window * 19 + (next_price - price + 9) as usize) % WINDOWS;
+            price = next_price;
+
+            if step >= 3 && seen[window] != buyer {
+                seen[window] = buyer;
+                totals[window] += price;
+            }
+        }
+    }
+
+    Ok(totals.into_iter().max().unwrap_or_default())
+}
+
+static MY_INPUT: &str = aoc24::load_input!();
+
+fn main() -> Result<(), ParseError> {
+    println!("Part 1: {}", part_1(MY_INPUT)?);
+    println!("Part 2: {}", part_2(MY_INPUT)?);
+    Ok(())
+}
+
+#[test]
+fn check_secret_numbers() {
+    assert_eq!(
+        SecretNumbers(123).take(10).collect::<Vec<_>>(),
+        [
+            15887950, 16495136, 527345, 704524, 1553684, 12683156, 11100544, 12249484, 7753432,
+            5908254
+        ]
+    );
+    assert_eq!(SecretNumbers(1).nth(1999), Some(8685429));
+}
+
+aoc24::examples! {
+    part_1: [
+        example: TEST_INPUT_PART_1 => 37327623,
+    ],
+    part_2: [
+        example: TEST_INPUT_PART_2 => 23,
+    ],
+}
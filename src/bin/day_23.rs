@@ -0,0 +1,88 @@
+use aoc24::{
+    clique::{maximum_clique, triangles},
+    parse::{self, ParseError},
+};
+use itertools::Itertools;
+use petgraph::graphmap::UnGraphMap;
+
+static TEST_INPUT_PART_1: &str = "kh-tc
+qp-kh
+de-cg
+ka-co
+yn-aq
+qp-ub
+cg-tb
+vc-aq
+tb-ka
+wh-tc
+yn-cg
+kh-ub
+ta-co
+de-co
+tc-td
+tb-wq
+wh-td
+ta-ka
+td-qp
+aq-cg
+wq-ub
+ub-vc
+de-ta
+wq-aq
+wq-vc
+wh-yn
+ka-de
+kh-ta
+co-tc
+wh-qp
+tb-vc
+td-yn
+";
+
+static TEST_INPUT_PART_2: &str = TEST_INPUT_PART_1;
+
+fn parse(input: &str) -> Result<UnGraphMap<&str, ()>, ParseError> {
+    let edges = parse::lines(input, |x| {
+        let (a, b) = x.split_once("-", "a-b")?;
+        Ok((a.text, b.text))
+    })?;
+    Ok(UnGraphMap::from_edges(edges))
+}
+
+fn part_1(input: &str) -> Result<i64, ParseError> {
+    let graph = parse(input)?;
+    Ok(triangles(&graph)
+        .iter()
+        .filter(|triangle| triangle.iter().any(|x| x.starts_with('t')))
+        .count() as i64)
+}
+
+fn part_2(input: &str) -> Result<String, ParseError> {
+    let graph = parse(input)?;
+    Ok(maximum_clique(&graph).iter().join(","))
+}
+
+static MY_INPUT: &str = aoc24::load_input!();
+
+fn main() -> Result<(), ParseError> {
+    println!("Part 1: {}", part_1(MY_INPUT)?);
+    println!("Part 2: {}", part_2(MY_INPUT)?);
+    Ok(())
+}
+
+aoc24::examples! {
+    part_1: [
+        example: TEST_INPUT_PART_1 => 7,
+    ],
+    part_2: [
+        example: TEST_INPUT_PART_2 => "co,de,ka,ta",
+    ],
+}
+
+#[test]
+fn check_triangles() {
+    assert_eq!(triangles(&parse(TEST_INPUT_PART_1).unwrap()).len(), 12);
+
+    let error = part_1("kh-tc\nqp kh\n").unwrap_err();
+    assert_eq!(error.to_string(), "line 2: expected `a-b`");
+}
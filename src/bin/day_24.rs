@@ -0,0 +1,367 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fmt::Write,
+};
+
+use aoc24::parse::{ParseError, Span};
+use itertools::Itertools;
+use petgraph::{algo::toposort, graphmap::DiGraphMap};
+
+static TEST_INPUT_PART_1: &str = "x00: 1
+x01: 1
+x02: 1
+y00: 0
+y01: 1
+y02: 0
+
+x00 AND y00 -> z00
+x01 XOR y01 -> z01
+x02 OR y02 -> z02
+";
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Operation {
+    And,
+    Or,
+    Xor,
+}
+
+impl Operation {
+    fn apply(self, a: bool, b: bool) -> bool {
+        match self {
+            Operation::And => a & b,
+            Operation::Or => a | b,
+            Operation::Xor => a ^ b,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Operation::And => "AND",
+            Operation::Or => "OR",
+            Operation::Xor => "XOR",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Gate<'a> {
+    inputs: [&'a str; 2],
+    operation: Operation,
+    output: &'a str,
+}
+
+struct Circuit<'a> {
+    initial: BTreeMap<&'a str, bool>,
+    gates: Vec<Gate<'a>>,
+}
+
+fn parse(input: &str) -> Result<Circuit<'_>, ParseError> {
+    let (initial, gates) = Span::new(input).split_once("\n\n", "blank line")?;
+
+    let initial = initial
+        .lines()
+        .map(|x| {
+            let (wire, value) = x.split_once(": ", "wire: value")?;
+            match value.text {
+                "0" => Ok((wire.text, false)),
+                "1" => Ok((wire.text, true)),
+                _ => Err(value.error("0 or 1")),
+            }
+        })
+        .collect::<Result<_, _>>()?;
+
+    let gates = gates
+        .lines()
+        .map(|x| {
+            let (a, operation, b, arrow, output) = x
+                .split_whitespace()
+                .collect_tuple()
+                .ok_or_else(|| x.error("a GATE b -> c"))?;
+            if arrow.text != "->" {
+                return Err(arrow.error("->"));
+            }
+            let operation = match operation.text {
+                "AND" => Operation::And,
+                "OR" => Operation::Or,
+                "XOR" => Operation::Xor,
+                _ => return Err(operation.error("AND, OR or XOR")),
+            };
+            Ok(Gate {
+                inputs: [a.text, b.text],
+                operation,
+                output: output.text,
+            })
+        })
+        .collect::<Result<_, _>>()?;
+
+    Ok(Circuit { initial, gates })
+}
+
+/// A reason a gate's output wire can't be right if the circuit is meant to
+/// be a ripple-carry adder.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Violation {
+    /// Every `z` output apart from the last is the XOR of the input bits and
+    /// the carry.
+    OutputNotXor,
+    /// A XOR gate that doesn't produce an output bit has to be adding the two
+    /// input bits.
+    XorNotOnInputsOrOutput,
+    /// The XOR of two input bits has to go on to another XOR gate to be added
+    /// to the carry.
+    InputXorNotUsedByXor,
+    /// An AND gate is part of working out a carry, so it has to go into the
+    /// OR gate that combines the carries.
+    AndNotUsedByOr,
+}
+
+impl<'a> Circuit<'a> {
+    /// Works out the value of every wire, evaluating the gates in topological
+    /// order of the wires.
+    fn simulate(&self) -> HashMap<&'a str, bool> {
+        let mut graph = DiGraphMap::<&str, usize>::new();
+        for (idx, gate) in self.gates.iter().enumerate() {
+            for input in gate.inputs {
+                graph.add_edge(input, gate.output, idx);
+            }
+        }
+
+        let producers: HashMap<&str, &Gate> =
+            self.gates.iter().map(|gate| (gate.output, gate)).collect();
+
+        let order = toposort(&graph, None).expect("circuit should not have any loops");
+
+        let mut values: HashMap<&str, bool> = self.initial.iter().map(|(&k, &v)| (k, v)).collect();
+        for wire in order {
+            if let Some(gate) = producers.get(wire) {
+                let value = gate
+                    .operation
+                    .apply(values[gate.inputs[0]], values[gate.inputs[1]]);
+                values.insert(wire, value);
+            }
+        }
+
+        values
+    }
+
+    fn number(values: &HashMap<&str, bool>, prefix: char) -> i64 {
+        values
+            .iter()
+            .filter(|(_, &value)| value)
+            // wires like `zab` are allowed, but only numbered ones are bits
+            .filter_map(|(wire, _)| wire.strip_prefix(prefix)?.parse::<u32>().ok())
+            .map(|bit| 1 << bit)
+            .sum()
+    }
+
+    /// Checks every gate against the structure of a ripple-carry adder,
+    /// returning the output wires of gates that break the rules.
+    fn check_adder(&self) -> Vec<(&'a str, Violation)> {
+        let last_output = self
+            .gates
+            .iter()
+            .map(|gate| gate.output)
+            .filter(|wire| wire.starts_with('z'))
+            .max()
+            .unwrap_or_default();
+
+        let is_input = |wire: &str| wire.starts_with('x') || wire.starts_with('y');
+        let is_first_bit = |gate: &Gate| gate.inputs.iter().all(|x| x[1..] == *"00");
+        let used_by = |wire: &str, operation: Operation| {
+            self.gates
+                .iter()
+                .any(|gate| gate.operation == operation && gate.inputs.contains(&wire))
+        };
+
+        let mut violations = Vec::new();
+        for gate in &self.gates {
+            let output = gate.output;
+            let from_inputs = gate.inputs.iter().all(|x| is_input(x));
+
+            let violation = match gate.operation {
+                _ if output.starts_with('z')
+                    && output != last_output
+                    && gate.operation != Operation::Xor =>
+                {
+                    Some(Violation::OutputNotXor)
+                }
+                Operation::Xor if !from_inputs && !output.starts_with('z') => {
+                    Some(Violation::XorNotOnInputsOrOutput)
+                }
+                Operation::Xor
+                    if from_inputs && !is_first_bit(gate) && !used_by(output, Operation::Xor) =>
+                {
+                    Some(Violation::InputXorNotUsedByXor)
+                }
+                Operation::And if !is_first_bit(gate) && !used_by(output, Operation::Or) => {
+                    Some(Violation::AndNotUsedByOr)
+                }
+                _ => None,
+            };
+
+            if let Some(violation) = violation {
+                violations.push((output, violation));
+            }
+        }
+
+        violations
+    }
+
+    /// The circuit as a Graphviz graph, with wires as ellipses, gates as boxes
+    /// and any wires that break the adder rules in red.
+    fn to_dot(&self) -> String {
+        let suspicious: BTreeSet<&str> = self.check_adder().into_iter().map(|(x, _)| x).collect();
+
+        let mut dot = String::from("digraph circuit {\n");
+        let wires: BTreeSet<&str> = self
+            .gates
+            .iter()
+            .flat_map(|gate| gate.inputs.into_iter().chain([gate.output]))
+            .collect();
+        for wire in wires {
+            let colour = if suspicious.contains(wire) {
+                "red"
+            } else {
+                "black"
+            };
+            writeln!(dot, "  {wire} [shape=ellipse, color={colour}];").unwrap();
+        }
+        for (idx, gate) in self.gates.iter().enumerate() {
+            writeln!(
+                dot,
+                "  gate{idx} [shape=box, label={}];",
+                gate.operation.name()
+            )
+            .unwrap();
+            for input in gate.inputs {
+                writeln!(dot, "  {input} -> gate{idx};").unwrap();
+            }
+            writeln!(dot, "  gate{idx} -> {};", gate.output).unwrap();
+        }
+        dot.push_str("}\n");
+
+        dot
+    }
+}
+
+fn part_1(input: &str) -> Result<i64, ParseError> {
+    let circuit = parse(input)?;
+    Ok(Circuit::number(&circuit.simulate(), 'z'))
+}
+
+fn part_2(input: &str) -> Result<String, ParseError> {
+    Ok(parse(input)?
+        .check_adder()
+        .into_iter()
+        .map(|(wire, _)| wire)
+        .sorted()
+        .dedup()
+        .join(","))
+}
+
+static MY_INPUT: &str = aoc24::load_input!();
+
+fn main() -> Result<(), ParseError> {
+    if std::env::args().any(|x| x == "--dot") {
+        print!("{}", parse(MY_INPUT)?.to_dot());
+        return Ok(());
+    }
+
+    println!("Part 1: {}", part_1(MY_INPUT)?);
+    println!("Part 2: {}", part_2(MY_INPUT)?);
+    Ok(())
+}
+
+#[cfg(test)]
+fn ripple_carry_adder(bits: usize, x: i64, y: i64, swaps: &[(&str, &str)]) -> String {
+    let mut input = String::new();
+    for (prefix, value) in [('x', x), ('y', y)] {
+        for bit in 0..bits {
+            writeln!(input, "{prefix}{bit:02}: {}", (value >> bit) & 1).unwrap();
+        }
+    }
+    input.push('\n');
+
+    let mut gates = Vec::new();
+    for bit in 0..bits {
+        let sum = if bit == 0 {
+            "z00".to_string()
+        } else {
+            format!("sum{bit:02}")
+        };
+        let carry = format!("cry{bit:02}");
+
+        gates.push(format!("x{bit:02} XOR y{bit:02} -> {sum}"));
+        if bit == 0 {
+            gates.push(format!("x{bit:02} AND y{bit:02} -> {carry}"));
+        } else {
+            let previous = format!("cry{:02}", bit - 1);
+            gates.push(format!("x{bit:02} AND y{bit:02} -> and{bit:02}"));
+            gates.push(format!("{sum} XOR {previous} -> z{bit:02}"));
+            gates.push(format!("{sum} AND {previous} -> crr{bit:02}"));
+            let carry = if bit == bits - 1 {
+                format!("z{bits:02}")
+            } else {
+                carry
+            };
+            gates.push(format!("and{bit:02} OR crr{bit:02} -> {carry}"));
+        }
+    }
+
+    for gate in gates {
+        let (gate, mut output) = gate.split_once(" -> ").unwrap();
+        for &(a, b) in swaps {
+            if output == a {
+                output = b;
+            } else if output == b {
+                output = a;
+            }
+        }
+        writeln!(input, "{gate} -> {output}").unwrap();
+    }
+
+    input
+}
+
+aoc24::examples! {
+    part_1: [
+        example: TEST_INPUT_PART_1 => 4,
+    ],
+}
+
+#[test]
+fn check_adder() {
+    let input = ripple_carry_adder(8, 173, 94, &[]);
+    assert_eq!(part_1(&input), Ok(173 + 94));
+    assert_eq!(part_2(&input), Ok(String::new()));
+
+    let input = ripple_carry_adder(8, 173, 94, &[("z03", "and03"), ("sum05", "crr05")]);
+    assert_eq!(part_2(&input).unwrap(), "and03,crr05,sum05,z03");
+
+    assert_eq!(part_1("x00: 1\n\nx00 OR x00 -> zab\n"), Ok(0));
+}
+
+#[test]
+fn check_dot() {
+    let dot = parse(TEST_INPUT_PART_1).unwrap().to_dot();
+    assert!(dot.starts_with("digraph circuit {\n"));
+    assert!(dot.contains(
+        "  gate1 [shape=box, label=XOR];\n  x01 -> gate1;\n  y01 -> gate1;\n  gate1 -> z01;\n"
+    ));
+    assert!(dot.ends_with("}\n"));
+}
+
+#[test]
+fn check_parse_errors() {
+    let error = part_1("x00: 1\n\nx00 NAND y00 -> z00\n").unwrap_err();
+    assert_eq!((error.line, error.column), (3, 5));
+    assert_eq!(error.to_string(), "line 3: expected `AND, OR or XOR`");
+
+    let error = part_1("x00: 1\n\nx00 AND y00 z00\n").unwrap_err();
+    assert_eq!(error.to_string(), "line 3: expected `a GATE b -> c`");
+    let error = part_1("x00: 1\n\nx00 AND y00 => z00\n").unwrap_err();
+    assert_eq!((error.line, error.column), (3, 13));
+    let error = part_1("x00: 2\n\nx00 AND y00 -> z00\n").unwrap_err();
+    assert_eq!(error.to_string(), "line 1: expected `0 or 1`");
+}
@@ -0,0 +1,199 @@
+use aoc24::{
+    parse::{ParseError, Span},
+    Grid, Vector2d,
+};
+
+static TEST_INPUT_PART_1: &str = "#####
+.####
+.####
+.####
+.#.#.
+.#...
+.....
+
+#####
+##.##
+.#.##
+...##
+...#.
+...#.
+.....
+
+.....
+#....
+#....
+#...#
+#.#.#
+#.###
+#####
+
+.....
+.....
+#.#..
+###..
+###.#
+###.#
+#####
+
+.....
+.....
+.....
+#....
+#.#..
+#.#.#
+#####
+";
+
+const COLUMNS: usize = 5;
+const SPACE: u32 = 5;
+const ROWS: usize = SPACE as usize + 2;
+
+#[derive(Debug, PartialEq, Eq)]
+enum Schematic {
+    Lock([u32; COLUMNS]),
+    Key([u32; COLUMNS]),
+}
+
+impl Schematic {
+    fn new(input: Span<'_>) -> Result<Self, ParseError> {
+        let grid = Grid::parse(input)?;
+        if grid.width != COLUMNS {
+            return Err(input.error(&format!("a row {COLUMNS} wide")));
+        }
+        if grid.height != ROWS {
+            return Err(input.error(&format!("{ROWS} rows")));
+        }
+
+        // a lock has a full top row and a key a full bottom row, with the
+        // row at the other end empty
+        let row_is = |y: usize, cell: u8| {
+            (0..COLUMNS as i32).all(|x| grid.get(Vector2d(x, y as i32)) == Some(cell))
+        };
+        let is_lock = row_is(0, b'#');
+        let (full, empty) = if is_lock {
+            (0, ROWS - 1)
+        } else {
+            (ROWS - 1, 0)
+        };
+        let row = |y: usize| input.lines().nth(y).unwrap();
+        if !row_is(full, b'#') {
+            return Err(row(full).error("a row of #"));
+        }
+        if !row_is(empty, b'.') {
+            return Err(row(empty).error("a row of ."));
+        }
+
+        let mut heights = [0; COLUMNS];
+        for (position, x) in grid.iter() {
+            if x == b'#' {
+                heights[position.0 as usize] += 1;
+            }
+        }
+        // the full top or bottom row isn't part of the height
+        let heights = heights.map(|x: u32| x - 1);
+
+        Ok(if is_lock {
+            Schematic::Lock(heights)
+        } else {
+            Schematic::Key(heights)
+        })
+    }
+
+    /// Packs the profile into a bitmask of the space between the top and
+    /// bottom rows, with 5 bits per column and row 0 at the top. Locks fill
+    /// each column from the top and keys from the bottom, so a key fits a
+    /// lock exactly when their masks have no bits in common.
+    fn mask(&self) -> u32 {
+        let (heights, from_bottom) = match self {
+            Schematic::Lock(heights) => (heights, false),
+            Schematic::Key(heights) => (heights, true),
+        };
+
+        heights
+            .iter()
+            .enumerate()
+            .map(|(column, &h)| {
+                let bits = (1 << h) - 1;
+                let bits = if from_bottom {
+                    bits << (SPACE - h)
+                } else {
+                    bits
+                };
+                bits << (column as u32 * SPACE)
+            })
+            .fold(0, |acc, x| acc | x)
+    }
+}
+
+fn parse(input: &str) -> Result<(Vec<u32>, Vec<u32>), ParseError> {
+    let mut locks = Vec::new();
+    let mut keys = Vec::new();
+    for schematic in Span::new(input).split("\n\n") {
+        let schematic = Schematic::new(schematic)?;
+        match schematic {
+            Schematic::Lock(_) => locks.push(schematic.mask()),
+            Schematic::Key(_) => keys.push(schematic.mask()),
+        }
+    }
+    Ok((locks, keys))
+}
+
+fn part_1(input: &str) -> Result<i64, ParseError> {
+    let (locks, keys) = parse(input)?;
+    Ok(locks
+        .iter()
+        .map(|lock| keys.iter().filter(|&key| lock & key == 0).count() as i64)
+        .sum())
+}
+
+static MY_INPUT: &str = aoc24::load_input!();
+
+fn main() -> Result<(), ParseError> {
+    println!("Part 1: {}", part_1(MY_INPUT)?);
+    Ok(())
+}
+
+aoc24::examples! {
+    part_1: [
+        example: TEST_INPUT_PART_1 => 3,
+    ],
+}
+
+#[test]
+fn check_schematics() {
+    let schematics: Vec<_> = Span::new(TEST_INPUT_PART_1)
+        .split("\n\n")
+        .map(|x| Schematic::new(x).unwrap())
+        .collect();
+    assert_eq!(schematics[0], Schematic::Lock([0, 5, 3, 4, 3]));
+    assert_eq!(schematics[2], Schematic::Key([5, 0, 2, 1, 3]));
+
+    // the first lock and key overlap only in the last column
+    assert_eq!(
+        schematics[0].mask() & schematics[2].mask(),
+        0b00100 << (4 * SPACE)
+    );
+}
+
+#[test]
+fn check_parse_errors() {
+    let lock = "#####\n.####\n.####\n.####\n.#.#.\n.#...\n.....\n";
+    assert_eq!(part_1(lock), Ok(0));
+
+    let error = |input: &str| part_1(input).unwrap_err().to_string();
+    assert_eq!(error(""), "line 1: expected `a grid`");
+    assert_eq!(
+        error(&format!("{lock}\n######\n")),
+        "line 9: expected `a row 5 wide`"
+    );
+    assert_eq!(error("#####\n.....\n"), "line 1: expected `7 rows`");
+    // a column with no `#` in it at all
+    assert_eq!(
+        error(".....\n.....\n.....\n.....\n.....\n.....\n####.\n"),
+        "line 7: expected `a row of #`"
+    );
+    assert_eq!(
+        error("#####\n.####\n.####\n.####\n.#.#.\n.#...\n..#..\n"),
+        "line 7: expected `a row of .`"
+    );
+}
@@ -0,0 +1,114 @@
+//! Cliques in undirected graphs: sets of nodes that are all connected to each
+//! other.
+
+use std::collections::BTreeSet;
+
+use petgraph::graphmap::{NodeTrait, UnGraphMap};
+
+/// Every triangle in the graph, each listed once with its nodes in ascending
+/// order.
+pub fn triangles<N: NodeTrait>(graph: &UnGraphMap<N, ()>) -> Vec<[N; 3]> {
+    let mut triangles = Vec::new();
+
+    for a in graph.nodes() {
+        for b in graph.neighbors(a).filter(|&b| b > a) {
+            for c in graph.neighbors(b).filter(|&c| c > b) {
+                if graph.contains_edge(a, c) {
+                    triangles.push([a, b, c]);
+                }
+            }
+        }
+    }
+
+    triangles
+}
+
+/// Calls `f` with every maximal clique, that is every clique that can't have
+/// another node added to it, using Bron–Kerbosch with pivoting.
+pub fn for_each_maximal_clique<N: NodeTrait>(graph: &UnGraphMap<N, ()>, mut f: impl FnMut(&[N])) {
+    fn bron_kerbosch<N: NodeTrait>(
+        graph: &UnGraphMap<N, ()>,
+        clique: &mut Vec<N>,
+        mut candidates: BTreeSet<N>,
+        mut excluded: BTreeSet<N>,
+        f: &mut impl FnMut(&[N]),
+    ) {
+        // the pivot is the node with the most candidates as neighbours, since
+        // any maximal clique contains either it or one of its non-neighbours
+        let Some(pivot) = candidates
+            .iter()
+            .chain(&excluded)
+            .copied()
+            .max_by_key(|&u| {
+                graph
+                    .neighbors(u)
+                    .filter(|v| candidates.contains(v))
+                    .count()
+            })
+        else {
+            f(clique);
+            return;
+        };
+
+        let to_try: Vec<N> = candidates
+            .iter()
+            .copied()
+            .filter(|&v| !graph.contains_edge(pivot, v))
+            .collect();
+
+        for v in to_try {
+            clique.push(v);
+            bron_kerbosch(
+                graph,
+                clique,
+                graph
+                    .neighbors(v)
+                    .filter(|x| candidates.contains(x))
+                    .collect(),
+                graph
+                    .neighbors(v)
+                    .filter(|x| excluded.contains(x))
+                    .collect(),
+                f,
+            );
+            clique.pop();
+
+            candidates.remove(&v);
+            excluded.insert(v);
+        }
+    }
+
+    bron_kerbosch(
+        graph,
+        &mut Vec::new(),
+        graph.nodes().collect(),
+        BTreeSet::new(),
+        &mut f,
+    );
+}
+
+/// The largest clique in the graph, with its nodes in ascending order.
+pub fn maximum_clique<N: NodeTrait>(graph: &UnGraphMap<N, ()>) -> Vec<N> {
+    let mut largest: Vec<N> = Vec::new();
+    for_each_maximal_clique(graph, |clique| {
+        if clique.len() > largest.len() {
+            largest = clique.to_vec();
+        }
+    });
+    largest.sort();
+    largest
+}
+
+#[test]
+fn check_maximal_cliques() {
+    // a square with one diagonal is two triangles sharing an edge
+    let graph = UnGraphMap::<i32, ()>::from_edges([(1, 2), (2, 3), (3, 4), (4, 1), (1, 3)]);
+    let mut cliques = Vec::new();
+    for_each_maximal_clique(&graph, |clique| {
+        let mut clique = clique.to_vec();
+        clique.sort();
+        cliques.push(clique);
+    });
+    cliques.sort();
+    assert_eq!(cliques, [[1, 2, 3], [1, 3, 4]]);
+}
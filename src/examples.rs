@@ -0,0 +1,217 @@
+//! Worked examples from the puzzle descriptions, kept as fixtures in
+//! `fixtures/`. Unlike the real inputs, which live in a private submodule,
+//! these are checked in. Each example is a `day_NN_K.txt` holding the input
+//! and a `day_NN_K.answers` holding lines like `part_1: 143`.
+
+use std::{
+    fmt::Display,
+    fs,
+    path::{Path, PathBuf},
+};
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Example {
+    pub name: String,
+    pub input: String,
+    pub answers: Vec<(String, String)>,
+}
+
+impl Example {
+    pub fn answer(&self, part: &str) -> Option<&str> {
+        self.answers
+            .iter()
+            .find(|(x, _)| x == part)
+            .map(|(_, answer)| answer.as_str())
+    }
+}
+
+pub fn directory(root: &Path) -> PathBuf {
+    root.join("fixtures")
+}
+
+/// Every example saved for `day`, which is the name of the day's binary like
+/// `day_05`, in the order they were saved.
+pub fn load(root: &Path, day: &str) -> Vec<Example> {
+    let Ok(dir) = fs::read_dir(directory(root)) else {
+        return Vec::new();
+    };
+
+    let prefix = format!("{day}_");
+    let mut names: Vec<_> = dir
+        .filter_map(|x| x.ok()?.file_name().into_string().ok())
+        .filter_map(|x| Some(x.strip_suffix(".txt")?.to_string()))
+        .filter(|x| {
+            x.strip_prefix(&prefix)
+                .is_some_and(|k| k.parse::<u32>().is_ok())
+        })
+        .collect();
+    names.sort_by_key(|x| x[prefix.len()..].parse::<u32>().unwrap());
+
+    names
+        .into_iter()
+        .map(|name| {
+            let path = directory(root).join(&name);
+            let input = fs::read_to_string(path.with_extension("txt")).unwrap();
+            let answers = fs::read_to_string(path.with_extension("answers"))
+                .unwrap_or_default()
+                .lines()
+                .filter_map(|x| x.split_once(':'))
+                .map(|(part, answer)| (part.trim().to_string(), answer.trim().to_string()))
+                .collect();
+
+            Example {
+                name,
+                input,
+                answers,
+            }
+        })
+        .collect()
+}
+
+pub fn save(root: &Path, example: &Example) -> std::io::Result<()> {
+    let path = directory(root).join(&example.name);
+    fs::create_dir_all(directory(root))?;
+    fs::write(path.with_extension("txt"), &example.input)?;
+
+    let answers: String = example
+        .answers
+        .iter()
+        .map(|(part, answer)| format!("{part}: {answer}\n"))
+        .collect();
+    fs::write(path.with_extension("answers"), answers)
+}
+
+/// What a solver returned, as it would be printed. Solvers which parse their
+/// input return a `Result`, and a parse error is shown in its place.
+pub trait Answer {
+    fn answer(self) -> String;
+}
+
+macro_rules! impl_answer {
+    ($($t:ty),*) => {
+        $(impl Answer for $t {
+            fn answer(self) -> String {
+                self.to_string()
+            }
+        })*
+    };
+}
+
+impl_answer!(i32, i64, u32, u64, usize, String, &str, num_bigint::BigUint);
+
+impl<T: Answer, E: Display> Answer for Result<T, E> {
+    fn answer(self) -> String {
+        match self {
+            Ok(x) => x.answer(),
+            Err(e) => format!("error: {e}"),
+        }
+    }
+}
+
+/// Compares answers by how they print, so `143` can be checked against an
+/// `i64` or a `BigUint`, and reports them on lines of their own so multi-line
+/// answers diff cleanly.
+pub fn assert_answer(name: &str, expected: impl Display, found: impl Answer) {
+    let (expected, found) = (expected.to_string(), found.answer());
+    if expected != found {
+        panic!("{name}\nexpected: {expected}\n   found: {found}");
+    }
+}
+
+/// Checks `solve` against the example's answer for `part`, if it has one.
+pub fn check<T: Answer>(example: &Example, part: &str, solve: impl FnOnce(&str) -> T) {
+    if let Some(expected) = example.answer(part) {
+        assert_answer(
+            &format!("{} {}", example.name, part),
+            expected,
+            solve(&example.input),
+        );
+    }
+}
+
+/// Generates a test for each example, grouped into a module per solver, so
+/// ```ignore
+/// examples! {
+///     part_1: [example: TEST_INPUT_PART_1, TEST_SIZE => 12],
+/// }
+/// ```
+/// becomes a test `part_1::example` checking that
+/// `part_1(TEST_INPUT_PART_1, TEST_SIZE)` is `12`.
+#[macro_export]
+macro_rules! examples {
+    ($($solver:ident: [$($case:ident: $input:expr $(, $arg:expr)* => $expected:expr),* $(,)?]),* $(,)?) => {
+        $(
+            #[cfg(test)]
+            mod $solver {
+                use super::*;
+
+                $(
+                    #[test]
+                    fn $case() {
+                        $crate::examples::assert_answer(
+                            concat!(
+                                stringify!($solver),
+                                "(",
+                                stringify!($input),
+                                $(", ", stringify!($arg),)*
+                                ")"
+                            ),
+                            $expected,
+                            $solver($input $(, $arg)*),
+                        );
+                    }
+                )*
+            }
+        )*
+    };
+}
+
+/// Generates a test which checks every saved example of the day it is used in
+/// against the given solvers, for example
+/// `example_tests! { part_1: part_1, part_2: |x| part_2(x, 6) }`. The test
+/// fails if the day has no examples saved.
+#[macro_export]
+macro_rules! example_tests {
+    ($($part:ident: $solve:expr),+ $(,)?) => {
+        #[test]
+        fn check_examples() {
+            let day = ::std::path::Path::new(file!())
+                .file_stem()
+                .unwrap()
+                .to_str()
+                .unwrap();
+            let examples =
+                $crate::examples::load(::std::path::Path::new(env!("CARGO_MANIFEST_DIR")), day);
+            assert!(!examples.is_empty(), "no examples saved for {day}");
+
+            for example in &examples {
+                $($crate::examples::check(example, stringify!($part), $solve);)+
+            }
+        }
+    };
+}
+
+#[test]
+fn check_save_and_load() {
+    let dir = crate::testing::TempDir::new("examples");
+    let root = dir.path();
+
+    let examples = [2, 1, 10].map(|k| Example {
+        name: format!("day_05_{k}"),
+        input: format!("input {k}\n"),
+        answers: vec![("part_1".to_string(), k.to_string())],
+    });
+    for example in &examples {
+        save(root, example).unwrap();
+    }
+    fs::write(directory(root).join("day_15_1.txt"), "").unwrap();
+
+    let loaded = load(root, "day_05");
+    assert_eq!(
+        loaded.iter().map(|x| x.name.as_str()).collect::<Vec<_>>(),
+        ["day_05_1", "day_05_2", "day_05_10"]
+    );
+    assert_eq!(loaded[0], examples[1]);
+    assert_eq!(loaded[2].answer("part_1"), Some("10"));
+    assert_eq!(loaded[2].answer("part_2"), None);
+}
@@ -1,4 +1,16 @@
-use std::ops::{Add, AddAssign, Neg, Sub};
+use std::{
+    error::Error,
+    fmt,
+    ops::{Add, AddAssign, Neg, Sub},
+};
+
+use parse::{ParseError, Span};
+
+pub mod clique;
+pub mod examples;
+pub mod parse;
+#[doc(hidden)]
+pub mod testing;
 
 #[macro_export]
 macro_rules! load_input {
@@ -7,6 +19,76 @@ macro_rules! load_input {
     };
 }
 
+#[cfg(feature = "parallel")]
+#[doc(hidden)]
+pub use rayon;
+
+/// Runs `$body` with `$iter` bound to `$items.iter()`, or `$items.par_iter()`
+/// when this crate is built with the `parallel` feature, for loops where each
+/// item can be worked on separately:
+/// ```ignore
+/// maybe_par_iter!(reports, |iter| iter.filter(|x| is_safe(x)).count())
+/// ```
+/// The iterator methods rayon has, including `map_init`, work either way.
+#[cfg(feature = "parallel")]
+#[macro_export]
+macro_rules! maybe_par_iter {
+    ($items:expr, |$iter:ident| $body:expr) => {{
+        use $crate::rayon::prelude::*;
+        let $iter = $items.par_iter();
+        $body
+    }};
+}
+
+#[cfg(not(feature = "parallel"))]
+#[macro_export]
+macro_rules! maybe_par_iter {
+    ($items:expr, |$iter:ident| $body:expr) => {{
+        use $crate::MapInit as _;
+        let $iter = $items.iter();
+        $body
+    }};
+}
+
+/// Rayon's `map_init` for ordinary iterators, so [`maybe_par_iter!`] loops
+/// can keep scratch space per thread. There's only one thread here, so
+/// `init` is called once.
+#[doc(hidden)]
+pub trait MapInit: Iterator + Sized {
+    fn map_init<T, R>(
+        self,
+        init: impl FnOnce() -> T,
+        mut f: impl FnMut(&mut T, Self::Item) -> R,
+    ) -> impl Iterator<Item = R> {
+        let mut state = init();
+        self.map(move |x| f(&mut state, x))
+    }
+}
+
+impl<I: Iterator> MapInit for I {}
+
+/// Any error, for solvers that can fail in more than one way. Returning an
+/// error from `main` prints it with `Debug`, so this shows it with `Display`.
+pub struct Report(Box<dyn Error>);
+
+impl<E: Error + 'static> From<E> for Report {
+    fn from(error: E) -> Self {
+        Self(Box::new(error))
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl fmt::Debug for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
 pub struct Grid {
     pub width: usize,
     pub height: usize,
@@ -58,6 +140,17 @@ impl Vector2d {
         Vector2d(0, 1),
     ];
 
+    pub const ALL_DIRECTIONS: [Vector2d; 8] = [
+        Vector2d(1, 0),
+        Vector2d(1, -1),
+        Vector2d(0, -1),
+        Vector2d(-1, -1),
+        Vector2d(-1, 0),
+        Vector2d(-1, 1),
+        Vector2d(0, 1),
+        Vector2d(1, 1),
+    ];
+
     pub fn neighbours(self) -> impl Iterator<Item = Vector2d> {
         Self::DIRECTIONS.into_iter().map(move |x| x + self)
     }
@@ -73,7 +166,7 @@ impl Vector2d {
 
 impl Grid {
     pub fn new(input: &str) -> Self {
-        let width = input.lines().next().unwrap().len();
+        let width = input.lines().next().map_or(0, str::len);
         let height = input.lines().count();
         Self {
             width,
@@ -82,6 +175,24 @@ impl Grid {
         }
     }
 
+    /// Like [`Grid::new`], but checks the input is a rectangle with at least
+    /// one cell.
+    pub fn parse(input: Span<'_>) -> Result<Self, ParseError> {
+        let width = input.lines().next().map_or(0, |x| x.text.len());
+        if width == 0 {
+            return Err(input.error("a grid"));
+        }
+        if let Some(line) = input.lines().find(|x| x.text.len() != width) {
+            return Err(line.error(&format!("a row {width} wide")));
+        }
+        Ok(Self::new(input.text))
+    }
+
+    /// The position of the first `letter` in reading order.
+    pub fn find(&self, letter: u8) -> Option<Vector2d> {
+        self.iter().find(|&(_, x)| x == letter).map(|(v, _)| v)
+    }
+
     pub fn get(&self, v: Vector2d) -> Option<u8> {
         if !(0..self.width as i32).contains(&v.0) {
             return None;
@@ -99,3 +210,246 @@ impl Grid {
             .map(|v| (v, self.get(v).unwrap()))
     }
 }
+
+/// A rectangular pattern of letters to look for in a [`Grid`], where `None`
+/// matches any letter.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Pattern {
+    pub width: usize,
+    pub height: usize,
+    pub cells: Box<[Option<u8>]>,
+}
+
+impl Pattern {
+    pub fn new(input: &str, wildcard: u8) -> Self {
+        let width = input.lines().map(|x| x.len()).max().unwrap_or_default();
+        let height = input.lines().count();
+
+        let mut cells = vec![None; width * height];
+        for (y, line) in input.lines().enumerate() {
+            for (x, letter) in line.bytes().enumerate() {
+                if letter != wildcard {
+                    cells[x + y * width] = Some(letter);
+                }
+            }
+        }
+
+        Self {
+            width,
+            height,
+            cells: cells.into(),
+        }
+    }
+
+    pub fn get(&self, v: Vector2d) -> Option<u8> {
+        self.cells[v.0 as usize + v.1 as usize * self.width]
+    }
+
+    /// The pattern turned a quarter turn clockwise.
+    pub fn rotate(&self) -> Self {
+        let mut cells = vec![None; self.cells.len()];
+        for y in 0..self.height {
+            for x in 0..self.width {
+                cells[(self.height - 1 - y) + x * self.height] = self.cells[x + y * self.width];
+            }
+        }
+
+        Self {
+            width: self.height,
+            height: self.width,
+            cells: cells.into(),
+        }
+    }
+
+    /// The pattern mirrored left to right.
+    pub fn reflect(&self) -> Self {
+        let mut cells = vec![None; self.cells.len()];
+        for y in 0..self.height {
+            for x in 0..self.width {
+                cells[(self.width - 1 - x) + y * self.width] = self.cells[x + y * self.width];
+            }
+        }
+
+        Self {
+            width: self.width,
+            height: self.height,
+            cells: cells.into(),
+        }
+    }
+
+    /// The distinct rotations and reflections of the pattern, along with how
+    /// many quarter turns clockwise each one is and whether it was mirrored
+    /// left to right before turning.
+    pub fn orientations(&self) -> Vec<(usize, bool, Pattern)> {
+        let mut orientations: Vec<(usize, bool, Pattern)> = Vec::new();
+        for (reflected, pattern) in [(false, self.clone()), (true, self.reflect())] {
+            let mut rotated = pattern;
+            for turns in 0..4 {
+                if orientations.iter().all(|(_, _, x)| *x != rotated) {
+                    orientations.push((turns, reflected, rotated.clone()));
+                }
+                rotated = rotated.rotate();
+            }
+        }
+        orientations
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct PatternMatch {
+    /// Where the top left corner of the rotated pattern is in the grid.
+    pub position: Vector2d,
+    /// How many quarter turns clockwise the pattern was rotated by.
+    pub rotation: usize,
+    /// Whether the pattern was mirrored left to right before it was rotated.
+    pub reflected: bool,
+}
+
+impl Grid {
+    fn matches_at(&self, pattern: &Pattern, position: Vector2d) -> bool {
+        (0..pattern.height as i32)
+            .flat_map(|y| (0..pattern.width as i32).map(move |x| Vector2d(x, y)))
+            .all(|v| match pattern.get(v) {
+                Some(letter) => self.get(position + v) == Some(letter),
+                None => self.get(position + v).is_some(),
+            })
+    }
+
+    /// Finds every occurrence of `word` written in a straight line in any of
+    /// the 8 directions, as the position of its first letter and the
+    /// direction it reads in.
+    pub fn find_word<'a>(
+        &'a self,
+        word: &'a str,
+    ) -> impl Iterator<Item = (Vector2d, Vector2d)> + use<'a> {
+        self.iter()
+            .flat_map(|(position, _)| {
+                Vector2d::ALL_DIRECTIONS.map(|direction| (position, direction))
+            })
+            .filter(move |&(position, direction)| {
+                let mut v = position;
+                word.bytes().all(|letter| {
+                    let found = self.get(v) == Some(letter);
+                    v += direction;
+                    found
+                })
+            })
+    }
+
+    /// Finds every occurrence of `pattern` in any of its rotations and
+    /// reflections.
+    pub fn find_pattern(&self, pattern: &Pattern) -> Vec<PatternMatch> {
+        let orientations = pattern.orientations();
+        self.iter()
+            .flat_map(|(position, _)| {
+                orientations
+                    .iter()
+                    .filter(move |(_, _, oriented)| self.matches_at(oriented, position))
+                    .map(move |&(rotation, reflected, _)| PatternMatch {
+                        position,
+                        rotation,
+                        reflected,
+                    })
+            })
+            .collect()
+    }
+}
+
+/// A connected area of cells that all have the same letter.
+#[derive(Clone, Debug)]
+pub struct Region {
+    pub kind: u8,
+    pub cells: Vec<Vector2d>,
+    pub perimeter: usize,
+    /// The number of straight sides of the fence around the region, which is
+    /// the same as the number of corners it has.
+    pub sides: usize,
+    /// The top left and bottom right cells of the smallest rectangle that
+    /// contains the region.
+    pub bounding_box: (Vector2d, Vector2d),
+}
+
+impl Region {
+    pub fn area(&self) -> usize {
+        self.cells.len()
+    }
+}
+
+pub struct Regions<'a> {
+    grid: &'a Grid,
+    labelled: Vec<bool>,
+    next: usize,
+}
+
+impl Iterator for Regions<'_> {
+    type Item = Region;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let grid = self.grid;
+        let index = |v: Vector2d| v.0 as usize + v.1 as usize * grid.width;
+
+        let start = (self.next..self.labelled.len()).find(|&idx| !self.labelled[idx])?;
+        self.next = start + 1;
+
+        let start = Vector2d((start % grid.width) as i32, (start / grid.width) as i32);
+        let kind = grid.get(start).unwrap();
+        let in_region = |v: Vector2d| grid.get(v) == Some(kind);
+
+        let mut cells = Vec::new();
+        let mut stack = vec![start];
+        self.labelled[index(start)] = true;
+
+        while let Some(position) = stack.pop() {
+            cells.push(position);
+            for neighbour in position.neighbours() {
+                if in_region(neighbour) && !self.labelled[index(neighbour)] {
+                    self.labelled[index(neighbour)] = true;
+                    stack.push(neighbour);
+                }
+            }
+        }
+
+        let mut perimeter = 0;
+        let mut sides = 0;
+        let mut min = start;
+        let mut max = start;
+
+        for &position in &cells {
+            min = Vector2d(min.0.min(position.0), min.1.min(position.1));
+            max = Vector2d(max.0.max(position.0), max.1.max(position.1));
+
+            for direction in Vector2d::DIRECTIONS {
+                let a = in_region(position + direction);
+                let b = in_region(position + direction.rotate());
+                let diagonal = in_region(position + direction + direction.rotate());
+
+                if !a {
+                    perimeter += 1;
+                }
+                if (!a && !b) || (a && b && !diagonal) {
+                    sides += 1;
+                }
+            }
+        }
+
+        Some(Region {
+            kind,
+            cells,
+            perimeter,
+            sides,
+            bounding_box: (min, max),
+        })
+    }
+}
+
+impl Grid {
+    /// Splits the grid into regions of the same letter, using an iterative
+    /// flood fill from the first cell not in any region so far.
+    pub fn regions(&self) -> Regions<'_> {
+        Regions {
+            grid: self,
+            labelled: vec![false; self.width * self.height],
+            next: 0,
+        }
+    }
+}
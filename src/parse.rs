@@ -0,0 +1,166 @@
+//! Parsing that keeps track of where it is in the input, so malformed input
+//! can be reported by line rather than with a panic.
+
+use std::{error::Error, fmt, str::FromStr};
+
+use regex::Regex;
+
+#[derive(Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub expected: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: expected `{}`", self.line, self.expected)
+    }
+}
+
+// returning an error from `main` prints it with `Debug`
+impl fmt::Debug for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl Error for ParseError {}
+
+/// Some of the input, along with the line and column (both counted from 1)
+/// it starts at.
+#[derive(Clone, Copy, Debug)]
+pub struct Span<'a> {
+    pub text: &'a str,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl<'a> Span<'a> {
+    pub fn new(input: &'a str) -> Self {
+        Self {
+            text: input,
+            line: 1,
+            column: 1,
+        }
+    }
+
+    pub fn error(&self, expected: &str) -> ParseError {
+        ParseError {
+            line: self.line,
+            column: self.column,
+            expected: expected.to_string(),
+        }
+    }
+
+    /// The span of `piece`, which has to be a slice of this span's text.
+    fn of(&self, piece: &'a str) -> Self {
+        let offset = piece.as_ptr() as usize - self.text.as_ptr() as usize;
+        let before = &self.text[..offset];
+
+        let (line, column) = match before.rfind('\n') {
+            Some(newline) => (
+                self.line + before.bytes().filter(|&x| x == b'\n').count(),
+                offset - newline,
+            ),
+            None => (self.line, self.column + offset),
+        };
+
+        Self {
+            text: piece,
+            line,
+            column,
+        }
+    }
+
+    /// The span starting `offset` bytes in.
+    pub fn skip(&self, offset: usize) -> Self {
+        self.of(&self.text[offset..])
+    }
+
+    pub fn trim(&self) -> Self {
+        self.of(self.text.trim())
+    }
+
+    pub fn lines(&self) -> impl Iterator<Item = Span<'a>> {
+        let span = *self;
+        span.text.lines().enumerate().map(move |(i, text)| Span {
+            text,
+            line: span.line + i,
+            column: if i == 0 { span.column } else { 1 },
+        })
+    }
+
+    pub fn split(&self, separator: &'a str) -> impl Iterator<Item = Span<'a>> {
+        let span = *self;
+        span.text.split(separator).map(move |x| span.of(x))
+    }
+
+    pub fn split_whitespace(&self) -> impl Iterator<Item = Span<'a>> {
+        let span = *self;
+        span.text.split_whitespace().map(move |x| span.of(x))
+    }
+
+    pub fn split_once(&self, separator: &str, expected: &str) -> Result<(Self, Self), ParseError> {
+        let (a, b) = self
+            .text
+            .split_once(separator)
+            .ok_or_else(|| self.error(expected))?;
+        Ok((self.of(a), self.of(b)))
+    }
+
+    pub fn strip_prefix(&self, prefix: &str) -> Result<Self, ParseError> {
+        let rest = self
+            .text
+            .strip_prefix(prefix)
+            .ok_or_else(|| self.error(prefix))?;
+        Ok(self.of(rest))
+    }
+
+    pub fn parse<T: FromStr>(&self, expected: &str) -> Result<T, ParseError> {
+        self.text.parse().map_err(|_| self.error(expected))
+    }
+
+    /// The spans of the regex's capture groups where it first matches.
+    pub fn captures<const N: usize>(
+        &self,
+        re: &Regex,
+        expected: &str,
+    ) -> Result<[Self; N], ParseError> {
+        let captures = re.captures(self.text).ok_or_else(|| self.error(expected))?;
+        Ok(std::array::from_fn(|i| {
+            self.of(captures.get(i + 1).map_or("", |x| x.as_str()))
+        }))
+    }
+}
+
+/// Parses `input` in order, if one thing is expected per line.
+pub fn lines<'a, T>(
+    input: &'a str,
+    mut f: impl FnMut(Span<'a>) -> Result<T, ParseError>,
+) -> Result<Vec<T>, ParseError> {
+    Span::new(input).lines().map(&mut f).collect()
+}
+
+#[test]
+fn check_positions() {
+    let input = Span::new("1|2\n3|4\n\n5,x,7\n");
+    let (rules, updates) = input.split_once("\n\n", "rules").unwrap();
+    assert_eq!((updates.line, updates.column), (4, 1));
+
+    let second = rules.lines().nth(1).unwrap();
+    let (a, b) = second.split_once("|", "a|b").unwrap();
+    assert_eq!((a.text, a.line, a.column), ("3", 2, 1));
+    assert_eq!((b.text, b.line, b.column), ("4", 2, 3));
+
+    let error = updates
+        .split(",")
+        .map(|x| x.parse::<i64>("integer"))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap_err();
+    assert_eq!((error.line, error.column), (4, 3));
+    assert_eq!(error.to_string(), "line 4: expected `integer`");
+
+    let error = lines("1|2\n3\n", |x| x.split_once("|", "a|b")).unwrap_err();
+    assert_eq!(error.to_string(), "line 2: expected `a|b`");
+}
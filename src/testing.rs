@@ -0,0 +1,32 @@
+//! Support for the tests of the library and the `aoc` binary. Not part of the
+//! API the days use.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// A scratch directory under the system temp directory, removed when dropped
+/// so a failing test doesn't leave it behind.
+pub struct TempDir(PathBuf);
+
+impl TempDir {
+    /// Creates an empty directory named after `name` and this process.
+    pub fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("aoc24-{name}-{}", std::process::id()));
+        // a leftover from a run that was killed before it could clean up
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+        TempDir(path)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}